@@ -0,0 +1,402 @@
+use super::{Bdd, BddManager, BddNode};
+use crate::collections::bitvectors::BitVector;
+use crate::logic::{BoolExpr, BoolOp, ModelCount};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+impl Bdd {
+    /// The constant `false` function (empty set).
+    pub const FALSE: Bdd = Bdd(0);
+    /// The constant `true` function (all valuations).
+    pub const TRUE: Bdd = Bdd(1);
+
+    pub fn is_false(self) -> bool {
+        return self == Bdd::FALSE;
+    }
+
+    pub fn is_true(self) -> bool {
+        return self == Bdd::TRUE;
+    }
+
+    /// **(internal)** True for the two constant nodes.
+    fn is_terminal(self) -> bool {
+        return self.0 < 2;
+    }
+}
+
+impl BddManager {
+    /// Create a manager for `num_vars` variables ordered by their index (variable `0` is at
+    /// the top of every diagram).
+    pub fn new(num_vars: usize) -> BddManager {
+        return BddManager::with_ordering((0..num_vars).collect());
+    }
+
+    /// Create a manager where `ordering[i]` is the variable tested at the `i`-th level of
+    /// every diagram (from the top). The ordering must be a permutation of `0..n`.
+    pub fn with_ordering(ordering: Vec<usize>) -> BddManager {
+        let num_vars = ordering.len();
+        let mut levels = vec![num_vars; num_vars];
+        for (level, variable) in ordering.iter().enumerate() {
+            if *variable >= num_vars || levels[*variable] != num_vars {
+                panic!("Ordering {:?} is not a permutation.", ordering);
+            }
+            levels[*variable] = level;
+        }
+        // Terminals use `num_vars` as their variable, so they are below all decision nodes.
+        let terminal = |value: bool| BddNode {
+            variable: num_vars,
+            low: Bdd(value as usize),
+            high: Bdd(value as usize),
+        };
+        return BddManager {
+            ordering,
+            levels,
+            nodes: vec![terminal(false), terminal(true)],
+            unique: HashMap::new(),
+            cache: HashMap::new(),
+        };
+    }
+
+    /// Same as `new`, but the manager is wrapped for sharing between `BddSet`s.
+    pub fn new_shared(num_vars: usize) -> Rc<RefCell<BddManager>> {
+        return Rc::new(RefCell::new(BddManager::new(num_vars)));
+    }
+
+    pub fn num_vars(&self) -> usize {
+        return self.ordering.len();
+    }
+
+    /// The variable ordering (`ordering()[i]` is tested at the `i`-th level from the top).
+    pub fn ordering(&self) -> &[usize] {
+        return &self.ordering;
+    }
+
+    /// Number of nodes stored in this manager (including the two terminals).
+    pub fn node_count(&self) -> usize {
+        return self.nodes.len();
+    }
+
+    /// Number of nodes reachable from `bdd` (including terminals).
+    pub fn size(&self, bdd: Bdd) -> usize {
+        return self.reachable(bdd).len();
+    }
+
+    /// **(internal)** All nodes reachable from `bdd` (including terminals).
+    fn reachable(&self, bdd: Bdd) -> HashSet<Bdd> {
+        let mut seen = HashSet::new();
+        let mut stack = vec![bdd];
+        while let Some(node) = stack.pop() {
+            if seen.insert(node) && !node.is_terminal() {
+                stack.push(self.nodes[node.0].low);
+                stack.push(self.nodes[node.0].high);
+            }
+        }
+        return seen;
+    }
+
+    pub fn constant(&self, value: bool) -> Bdd {
+        return if value { Bdd::TRUE } else { Bdd::FALSE };
+    }
+
+    /// The function which is true exactly when `variable` is true.
+    pub fn var(&mut self, variable: usize) -> Bdd {
+        if variable >= self.num_vars() {
+            panic!("Variable {} does not exist.", variable);
+        }
+        return self.mk(variable, Bdd::FALSE, Bdd::TRUE);
+    }
+
+    pub fn not(&mut self, bdd: Bdd) -> Bdd {
+        return self.apply(BoolOp::Xor, bdd, Bdd::TRUE);
+    }
+
+    /// Combine two functions using a binary operator.
+    pub fn apply(&mut self, op: BoolOp, left: Bdd, right: Bdd) -> Bdd {
+        if left.is_terminal() && right.is_terminal() {
+            return self.constant(op.apply(left.is_true(), right.is_true()));
+        }
+        match (op, left, right) {
+            (BoolOp::And, Bdd::FALSE, _) | (BoolOp::And, _, Bdd::FALSE) => return Bdd::FALSE,
+            (BoolOp::And, Bdd::TRUE, x) | (BoolOp::And, x, Bdd::TRUE) => return x,
+            (BoolOp::Or, Bdd::TRUE, _) | (BoolOp::Or, _, Bdd::TRUE) => return Bdd::TRUE,
+            (BoolOp::Or, Bdd::FALSE, x) | (BoolOp::Or, x, Bdd::FALSE) => return x,
+            _ => {}
+        }
+        if let Some(result) = self.cache.get(&(op, left, right)) {
+            return *result;
+        }
+        // Expand both operands by the top-most variable of the two.
+        let level = self.level(left).min(self.level(right));
+        let (left_low, left_high) = self.cofactors(left, level);
+        let (right_low, right_high) = self.cofactors(right, level);
+        let low = self.apply(op, left_low, right_low);
+        let high = self.apply(op, left_high, right_high);
+        let result = self.mk(self.ordering[level], low, high);
+        self.cache.insert((op, left, right), result);
+        return result;
+    }
+
+    /// Existentially quantify the given `variables`: the result is true for a valuation if
+    /// `bdd` is true for some valuation which differs only in `variables`.
+    pub fn exists(&mut self, bdd: Bdd, variables: &[usize]) -> Bdd {
+        let mut quantified = vec![false; self.num_vars()];
+        for variable in variables {
+            quantified[*variable] = true;
+        }
+        let mut cache = HashMap::new();
+        return self.exists_cached(bdd, &quantified, &mut cache);
+    }
+
+    /// Convert an expression to a BDD, interpreting atoms as variables.
+    ///
+    /// Panics if an atom is not a variable of this manager.
+    pub fn from_expr(&mut self, expr: &BoolExpr<usize>) -> Bdd {
+        return expr.fold(|node, children: Vec<Bdd>| match node {
+            BoolExpr::Const(value) => self.constant(*value),
+            BoolExpr::Atom(variable) => self.var(*variable),
+            BoolExpr::Not(_) => self.not(children[0]),
+            BoolExpr::Op { op, .. } => self.apply(*op, children[0], children[1]),
+            BoolExpr::And(_) => children
+                .into_iter()
+                .fold(Bdd::TRUE, |a, b| self.apply(BoolOp::And, a, b)),
+            BoolExpr::Or(_) => children
+                .into_iter()
+                .fold(Bdd::FALSE, |a, b| self.apply(BoolOp::Or, a, b)),
+        });
+    }
+
+    /// Evaluate the function of `bdd` for the given valuation of variables.
+    pub fn eval<BV: BitVector>(&self, bdd: Bdd, valuation: &BV) -> bool {
+        let mut node = bdd;
+        while !node.is_terminal() {
+            let BddNode {
+                variable,
+                low,
+                high,
+            } = self.nodes[node.0];
+            node = if valuation.get(variable) { high } else { low };
+        }
+        return node.is_true();
+    }
+
+    /// Some valuation for which `bdd` is true (or `None` if it is false everywhere).
+    ///
+    /// Variables which do not affect the result are set to `false`.
+    pub fn pick<BV: BitVector>(&self, bdd: Bdd) -> Option<BV> {
+        if bdd.is_false() {
+            return None;
+        }
+        let mut result = BV::empty(self.num_vars());
+        let mut node = bdd;
+        // In a reduced BDD, every non-terminal node has a path to `true`.
+        while !node.is_terminal() {
+            let BddNode {
+                variable,
+                low,
+                high,
+            } = self.nodes[node.0];
+            if low.is_false() {
+                result.set(variable, true);
+                node = high;
+            } else {
+                node = low;
+            }
+        }
+        return Some(result);
+    }
+
+    /// All paths of `bdd` which lead to `true`, as cubes over all variables (`cube[v]` is the
+    /// value of variable `v` on the path, or `None` if the path does not test it). The
+    /// cubes are pairwise disjoint and together cover exactly the valuations where `bdd`
+    /// is true.
+    pub fn cubes(&self, bdd: Bdd) -> Vec<Vec<Option<bool>>> {
+        let mut result = Vec::new();
+        let mut stack = vec![(bdd, vec![None; self.num_vars()])];
+        while let Some((node, cube)) = stack.pop() {
+            if node.is_true() {
+                result.push(cube);
+            } else if !node.is_false() {
+                let BddNode {
+                    variable,
+                    low,
+                    high,
+                } = self.nodes[node.0];
+                let mut high_cube = cube.clone();
+                high_cube[variable] = Some(true);
+                stack.push((high, high_cube));
+                let mut low_cube = cube;
+                low_cube[variable] = Some(false);
+                stack.push((low, low_cube));
+            }
+        }
+        return result;
+    }
+
+    /// The number of valuations (of all variables) for which `bdd` is true.
+    pub fn count(&self, bdd: Bdd) -> ModelCount {
+        // Number of models over the variables at or below the level of each node. Children
+        // are always created before their parents, so nodes can be processed by index.
+        let mut counts: HashMap<Bdd, ModelCount> = HashMap::new();
+        counts.insert(Bdd::FALSE, ModelCount::default());
+        counts.insert(Bdd::TRUE, ModelCount::from(1));
+        let mut reachable: Vec<Bdd> = self.reachable(bdd).into_iter().collect();
+        reachable.sort();
+        for node in reachable.into_iter().filter(|it| !it.is_terminal()) {
+            let BddNode { low, high, .. } = self.nodes[node.0];
+            let level = self.level(node);
+            let low_count = &counts[&low] << (self.level(low) - level - 1);
+            let high_count = &counts[&high] << (self.level(high) - level - 1);
+            counts.insert(node, low_count + &high_count);
+        }
+        return &counts[&bdd] << self.level(bdd);
+    }
+
+    /// **(internal)** Create (or find) a node, keeping the diagram reduced.
+    fn mk(&mut self, variable: usize, low: Bdd, high: Bdd) -> Bdd {
+        if low == high {
+            return low;
+        }
+        let node = BddNode {
+            variable,
+            low,
+            high,
+        };
+        if let Some(existing) = self.unique.get(&node) {
+            return *existing;
+        }
+        let result = Bdd(self.nodes.len());
+        self.nodes.push(node);
+        self.unique.insert(node, result);
+        return result;
+    }
+
+    /// **(internal)** The level of the variable tested in `bdd` (`num_vars` for terminals).
+    fn level(&self, bdd: Bdd) -> usize {
+        return if bdd.is_terminal() {
+            self.num_vars()
+        } else {
+            self.levels[self.nodes[bdd.0].variable]
+        };
+    }
+
+    /// **(internal)** Low and high cofactors of `bdd` with respect to the variable at `level`,
+    /// which must not be below the top level of `bdd`.
+    fn cofactors(&self, bdd: Bdd, level: usize) -> (Bdd, Bdd) {
+        return if self.level(bdd) == level {
+            (self.nodes[bdd.0].low, self.nodes[bdd.0].high)
+        } else {
+            (bdd, bdd)
+        };
+    }
+
+    fn exists_cached(
+        &mut self,
+        bdd: Bdd,
+        quantified: &[bool],
+        cache: &mut HashMap<Bdd, Bdd>,
+    ) -> Bdd {
+        if bdd.is_terminal() {
+            return bdd;
+        }
+        if let Some(result) = cache.get(&bdd) {
+            return *result;
+        }
+        let BddNode {
+            variable,
+            low,
+            high,
+        } = self.nodes[bdd.0];
+        let low = self.exists_cached(low, quantified, cache);
+        let high = self.exists_cached(high, quantified, cache);
+        let result = if quantified[variable] {
+            self.apply(BoolOp::Or, low, high)
+        } else {
+            self.mk(variable, low, high)
+        };
+        cache.insert(bdd, result);
+        return result;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::bdd::test_util::expr;
+    use crate::collections::bdd::{Bdd, BddManager};
+    use crate::collections::bitvectors::{BitVector, BitVector58};
+    use crate::logic::BoolOp;
+
+    #[test]
+    fn bdd_canonical() {
+        for ordering in [vec![0, 1, 2, 3], vec![3, 1, 0, 2]] {
+            let mut manager = BddManager::with_ordering(ordering);
+            let left = manager.from_expr(&expr("x0 & x1 | x2 ^ x3"));
+            let right = manager.from_expr(&expr("!(x3 <=> x2) | !(x0 => !x1)"));
+            assert_eq!(left, right);
+            let tautology = manager.from_expr(&expr("(x0 => x1) | (x1 => x2)"));
+            assert_eq!(Bdd::TRUE, tautology);
+            let contradiction = manager.from_expr(&expr("x0 & (x0 => x1) & !x1"));
+            assert!(contradiction.is_false());
+            let x = manager.var(2);
+            let not_not = manager.not(x);
+            assert_eq!(x, manager.not(not_not));
+        }
+    }
+
+    #[test]
+    fn bdd_matches_truth_table() {
+        let mut manager = BddManager::new(4);
+        let e = expr("(x0 ^ x1) & (x2 => x3) | !x0 & x3");
+        let bdd = manager.from_expr(&e);
+        for row in 0..16 {
+            let valuation =
+                BitVector58::from((0..4).map(|i| (row >> i) & 1 == 1).collect::<Vec<_>>());
+            assert_eq!(e.eval_bit_vector(&valuation), manager.eval(bdd, &valuation));
+        }
+        assert_eq!(
+            Some(e.truth_table(&[0, 1, 2, 3]).ones().len() as u64),
+            manager.count(bdd).to_u64()
+        );
+        let picked: BitVector58 = manager.pick(bdd).unwrap();
+        assert!(e.eval_bit_vector(&picked));
+        let cubes = manager.cubes(bdd);
+        let covered: usize = cubes
+            .iter()
+            .map(|c| 1 << c.iter().filter(|v| v.is_none()).count())
+            .sum();
+        assert_eq!(manager.count(bdd).to_u64(), Some(covered as u64));
+    }
+
+    #[test]
+    fn bdd_ordering_affects_size() {
+        // (x0 <=> x3) & (x1 <=> x4) & (x2 <=> x5) is small if the pairs are adjacent.
+        let e = expr("(x0 <=> x3) & (x1 <=> x4) & (x2 <=> x5)");
+        let mut good = BddManager::with_ordering(vec![0, 3, 1, 4, 2, 5]);
+        let mut bad = BddManager::new(6);
+        let (good_bdd, bad_bdd) = (good.from_expr(&e), bad.from_expr(&e));
+        assert!(good.size(good_bdd) < bad.size(bad_bdd));
+        assert_eq!(good.count(good_bdd), bad.count(bad_bdd));
+        assert_eq!(Some(8), good.count(good_bdd).to_u64());
+    }
+
+    #[test]
+    fn bdd_exists_and_large_counts() {
+        let mut manager = BddManager::new(100);
+        let x0 = manager.var(0);
+        let x99 = manager.var(99);
+        let both = manager.apply(BoolOp::And, x0, x99);
+        assert_eq!(x0, manager.exists(both, &[99]));
+        assert_eq!(Bdd::TRUE, manager.exists(both, &[0, 99]));
+        let either = manager.apply(BoolOp::Or, x0, x99);
+        assert_eq!(
+            "950737950171172051122527404032",
+            manager.count(either).to_string()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn bdd_invalid_ordering() {
+        BddManager::with_ordering(vec![0, 2, 0]);
+    }
+}
@@ -0,0 +1,314 @@
+use super::{Bdd, BddManager, BddNode, BddSet, BddSetIterator};
+use crate::collections::bitvectors::BitVector;
+use crate::collections::sets::{ElementSet, IterableSet, Set};
+use crate::logic::{BoolExpr, BoolOp, ModelCount};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+impl<BV: BitVector> BddSet<BV> {
+    /// Create a set of all valuations for which `root` is true.
+    pub fn new(manager: &Rc<RefCell<BddManager>>, root: Bdd) -> BddSet<BV> {
+        return BddSet {
+            manager: Some(manager.clone()),
+            root,
+            elements: PhantomData,
+        };
+    }
+
+    /// The set of all bit vectors of length `num_vars`.
+    pub fn universe(manager: &Rc<RefCell<BddManager>>) -> BddSet<BV> {
+        return BddSet::new(manager, Bdd::TRUE);
+    }
+
+    /// The set of all valuations which satisfy `expr` (atoms are interpreted as variables).
+    pub fn from_expr(manager: &Rc<RefCell<BddManager>>, expr: &BoolExpr<usize>) -> BddSet<BV> {
+        let root = manager.borrow_mut().from_expr(expr);
+        return BddSet::new(manager, root);
+    }
+
+    /// The BDD representing this set.
+    pub fn root(&self) -> Bdd {
+        return self.root;
+    }
+
+    /// The manager of this set (`None` for empty sets created by `Set::empty`).
+    pub fn manager(&self) -> Option<&Rc<RefCell<BddManager>>> {
+        return self.manager.as_ref();
+    }
+
+    /// The number of elements of this set.
+    pub fn count(&self) -> ModelCount {
+        return match &self.manager {
+            None => ModelCount::default(),
+            Some(manager) => manager.borrow().count(self.root),
+        };
+    }
+
+    /// **(internal)** Combine two sets using a binary operator. Sets without a manager
+    /// are empty, so the result is computed as if their root was `false`.
+    fn apply(&self, other: &Self, op: BoolOp) -> Self {
+        let manager = match (&self.manager, &other.manager) {
+            (Some(a), Some(b)) => {
+                if !Rc::ptr_eq(a, b) {
+                    panic!("Cannot combine sets from different BDD managers.");
+                }
+                a
+            }
+            (Some(a), None) | (None, Some(a)) => a,
+            (None, None) => return BddSet::empty(),
+        };
+        let root = manager.borrow_mut().apply(op, self.root, other.root);
+        return BddSet::new(manager, root);
+    }
+}
+
+impl<BV: BitVector> Set for BddSet<BV> {
+    fn empty() -> Self {
+        return BddSet {
+            manager: None,
+            root: Bdd::FALSE,
+            elements: PhantomData,
+        };
+    }
+
+    fn union(&self, other: &Self) -> Self {
+        return self.apply(other, BoolOp::Or);
+    }
+
+    fn intersect(&self, other: &Self) -> Self {
+        return self.apply(other, BoolOp::And);
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        // A \ B is the complement of A => B, i.e. A & !B.
+        let implication = self.apply(other, BoolOp::Imp);
+        return match &implication.manager {
+            None => BddSet::empty(),
+            Some(manager) => {
+                let root = manager.borrow_mut().not(implication.root);
+                BddSet::new(manager, root)
+            }
+        };
+    }
+
+    fn is_empty(&self) -> bool {
+        return self.root.is_false();
+    }
+
+    fn is_subset(&self, other: &Self) -> bool {
+        return self.minus(other).is_empty();
+    }
+}
+
+/// `BddSet`s can be used as parameter sets of parametrised graphs.
+impl<BV: BitVector> crate::param_graph::Params for BddSet<BV> {
+    fn union(&self, other: &Self) -> Self {
+        return Set::union(self, other);
+    }
+
+    fn intersect(&self, other: &Self) -> Self {
+        return Set::intersect(self, other);
+    }
+
+    fn minus(&self, other: &Self) -> Self {
+        return Set::minus(self, other);
+    }
+
+    fn is_empty(&self) -> bool {
+        return Set::is_empty(self);
+    }
+
+    fn is_subset(&self, other: &Self) -> bool {
+        return Set::is_subset(self, other);
+    }
+}
+
+impl<BV: BitVector> ElementSet for BddSet<BV> {
+    type Element = BV;
+
+    fn contains(&self, e: &Self::Element) -> bool {
+        return match &self.manager {
+            None => false,
+            Some(manager) => manager.borrow().eval(self.root, e),
+        };
+    }
+
+    fn pick(&self) -> Option<Self::Element> {
+        return self
+            .manager
+            .as_ref()
+            .and_then(|manager| manager.borrow().pick(self.root));
+    }
+}
+
+impl<BV: BitVector> IterableSet for BddSet<BV> {
+    type ElementIterator = BddSetIterator<BV>;
+
+    fn iter(&self) -> Self::ElementIterator {
+        let stack = match &self.manager {
+            None => Vec::new(),
+            Some(manager) => vec![(self.root, vec![None; manager.borrow().num_vars()])],
+        };
+        return BddSetIterator {
+            manager: self.manager.clone(),
+            stack,
+            expanding: None,
+        };
+    }
+}
+
+impl<BV: BitVector> Iterator for BddSetIterator<BV> {
+    type Item = BV;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((free, vector)) = &mut self.expanding {
+                let result = vector.clone();
+                // Increment the binary counter over free variables; once it overflows,
+                // the whole cube has been enumerated.
+                let position = free.iter().position(|i| !vector.get(*i));
+                for i in &free[..position.unwrap_or(free.len())] {
+                    vector.set(*i, false);
+                }
+                match position {
+                    Some(position) => vector.set(free[position], true),
+                    None => self.expanding = None,
+                }
+                return Some(result);
+            }
+            let (node, cube) = self.stack.pop()?;
+            if node.is_true() {
+                let free: Vec<usize> = (0..cube.len()).filter(|i| cube[*i].is_none()).collect();
+                let mut vector = BV::empty(cube.len());
+                for (i, value) in cube.iter().enumerate() {
+                    if *value == Some(true) {
+                        vector.set(i, true);
+                    }
+                }
+                self.expanding = Some((free, vector));
+            } else if !node.is_false() {
+                let manager = self.manager.as_ref().unwrap().borrow();
+                let BddNode {
+                    variable,
+                    low,
+                    high,
+                } = manager.nodes[node.0];
+                let mut high_cube = cube.clone();
+                high_cube[variable] = Some(true);
+                self.stack.push((high, high_cube));
+                let mut low_cube = cube;
+                low_cube[variable] = Some(false);
+                self.stack.push((low, low_cube));
+            }
+        }
+    }
+}
+
+impl<BV: BitVector> Eq for BddSet<BV> {}
+
+/// Sets are equal if they contain the same elements. Non-empty sets from different managers
+/// are never considered equal (even if their BDDs describe the same function).
+impl<BV: BitVector> PartialEq for BddSet<BV> {
+    fn eq(&self, other: &Self) -> bool {
+        // BDDs are canonical, so within one manager equal sets have equal roots.
+        return match (&self.manager, &other.manager) {
+            (Some(a), Some(b)) if Rc::ptr_eq(a, b) => self.root == other.root,
+            _ => self.is_empty() && other.is_empty(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::bdd::test_util::expr;
+    use crate::collections::bdd::{BddManager, BddSet};
+    use crate::collections::bitvectors::{ArrayBitVector, BitVector, BitVector58};
+    use crate::collections::sets::{ElementSet, IterableSet, Set};
+
+    #[test]
+    fn bdd_set_operations() {
+        let manager = BddManager::new_shared(3);
+        let a: BddSet<BitVector58> = BddSet::from_expr(&manager, &expr("x0 | x1"));
+        let b: BddSet<BitVector58> = BddSet::from_expr(&manager, &expr("x1 & x2"));
+        assert_eq!(
+            BddSet::from_expr(&manager, &expr("x1 & x2")),
+            a.intersect(&b)
+        );
+        assert_eq!(BddSet::from_expr(&manager, &expr("x0 | x1")), a.union(&b));
+        assert_eq!(
+            BddSet::from_expr(&manager, &expr("(x0 | x1) & !(x1 & x2)")),
+            a.minus(&b)
+        );
+        assert!(b.is_subset(&a) && !a.is_subset(&b));
+        assert_eq!(Some(6), a.count().to_u64());
+        assert!(a.minus(&a).is_empty());
+        assert_eq!(BddSet::empty(), a.minus(&a));
+    }
+
+    #[test]
+    fn bdd_set_empty_without_manager() {
+        let manager = BddManager::new_shared(2);
+        let empty: BddSet<BitVector58> = BddSet::empty();
+        let all = BddSet::universe(&manager);
+        assert!(empty.is_empty() && !all.is_empty());
+        assert_eq!(all, empty.union(&all));
+        assert_eq!(all, all.union(&empty));
+        assert_eq!(all, all.minus(&empty));
+        assert!(empty.minus(&all).is_empty());
+        assert!(empty.intersect(&all).is_empty());
+        assert!(empty.is_subset(&all) && !all.is_subset(&empty));
+        assert_eq!(None, empty.pick());
+        assert_eq!(0, empty.iter().count());
+        assert!(!empty.contains(&BitVector58::empty(2)));
+    }
+
+    #[test]
+    fn bdd_set_elements() {
+        let manager = BddManager::new_shared(4);
+        let e = expr("x0 ^ x3 | x1 & !x2");
+        let set: BddSet<ArrayBitVector> = BddSet::from_expr(&manager, &e);
+        let elements: Vec<ArrayBitVector> = set.iter().collect();
+        assert_eq!(set.count().to_u64(), Some(elements.len() as u64));
+        for element in &elements {
+            assert!(e.eval_bit_vector(element));
+            assert!(set.contains(element));
+        }
+        let mut rows: Vec<usize> = elements
+            .iter()
+            .map(|v| v.ones().into_iter().map(|i| 1 << i).sum())
+            .collect();
+        rows.sort();
+        assert_eq!(e.truth_table(&[0, 1, 2, 3]).ones(), rows);
+        assert!(e.eval_bit_vector(&set.pick().unwrap()));
+    }
+
+    #[test]
+    fn bdd_set_lazy_iterator() {
+        let manager = BddManager::new_shared(100);
+        let all: BddSet<ArrayBitVector> = BddSet::universe(&manager);
+        let first: Vec<Vec<usize>> = all.iter().take(4).map(|v| v.ones()).collect();
+        assert_eq!(vec![vec![], vec![0], vec![1], vec![0, 1]], first);
+        let set: BddSet<ArrayBitVector> = BddSet::from_expr(&manager, &expr("x2 & !x50"));
+        for element in set.iter().take(100) {
+            assert!(element.get(2) && !element.get(50));
+        }
+    }
+
+    #[test]
+    fn bdd_set_equality_across_managers() {
+        let a: BddSet<BitVector58> = BddSet::universe(&BddManager::new_shared(2));
+        let b: BddSet<BitVector58> = BddSet::universe(&BddManager::new_shared(2));
+        assert_ne!(a, b);
+        assert_eq!(a.minus(&a), b.minus(&b));
+        assert_eq!(BddSet::empty(), a.minus(&a));
+    }
+
+    #[test]
+    #[should_panic]
+    fn bdd_set_different_managers() {
+        let a: BddSet<BitVector58> = BddSet::universe(&BddManager::new_shared(2));
+        let b: BddSet<BitVector58> = BddSet::universe(&BddManager::new_shared(2));
+        a.union(&b);
+    }
+}
@@ -0,0 +1,109 @@
+//! Reduced ordered binary decision diagrams (BDDs), a symbolic representation of Boolean
+//! functions (and therefore of possibly huge sets of `BitVector`s).
+//!
+//! All BDDs live in a `BddManager` which owns the nodes. Thanks to the unique table of the
+//! manager, every function has exactly one node for a fixed variable ordering, so two `Bdd`
+//! handles of the same manager are equal if and only if they represent the same function.
+//! Results of operations are memoized in an operation cache. Nodes are never removed.
+//!
+//! ```rust
+//! use biodivine_lib_std::collections::bdd::BddManager;
+//! use biodivine_lib_std::logic::{BoolExpr, BoolOp};
+//! // Variable 2 is at the top of the diagram, variable 1 at the bottom.
+//! let mut manager = BddManager::with_ordering(vec![2, 0, 1]);
+//! let (a, b) = (manager.var(0), manager.var(1));
+//! let a_and_b = manager.apply(BoolOp::And, a, b);
+//! let expr: BoolExpr<usize> = BoolExpr::And(vec![BoolExpr::Atom(1), BoolExpr::Atom(0)]);
+//! assert_eq!(a_and_b, manager.from_expr(&expr));
+//! // Models of `a & b` over three variables.
+//! assert_eq!(Some(2), manager.count(a_and_b).to_u64());
+//! ```
+//!
+//! ### BDD sets
+//!
+//! `BddSet` wraps a `Bdd` (together with a shared reference to its manager) and implements
+//! `Set`, `ElementSet` and `IterableSet` over `BitVector`s of length `num_vars`, where the
+//! `i`-th bit is the value of variable `i`. Since `Set::empty` cannot refer to a manager,
+//! empty sets may have no manager at all (such sets are still equal to other empty sets).
+//! Non-empty sets from different managers can be compared, but are never equal (combining
+//! them with `union`, `intersect` or `minus` panics).
+//! `BddSet` also implements `param_graph::Params`, so it can represent parameter sets of
+//! parametrised graphs.
+//!
+//! ```rust
+//! use biodivine_lib_std::collections::bdd::{BddManager, BddSet};
+//! use biodivine_lib_std::collections::bitvectors::{BitVector, BitVector58};
+//! use biodivine_lib_std::collections::sets::{ElementSet, IterableSet, Set};
+//! use biodivine_lib_std::logic::BoolExpr;
+//! let manager = BddManager::new_shared(3);
+//! let expr = "x0 & !x2".parse::<BoolExpr<String>>().unwrap();
+//! let expr = expr.map_atoms(|atom| atom[1..].parse::<usize>().unwrap());
+//! let set: BddSet<BitVector58> = BddSet::from_expr(&manager, &expr);
+//! assert!(set.contains(&BitVector58::from(vec![true, true, false])));
+//! assert!(set.contains(&set.pick().unwrap()));
+//! assert_eq!(2, set.iter().count());
+//! let complement = BddSet::universe(&manager).minus(&set);
+//! assert_eq!(BddSet::empty(), set.intersect(&complement));
+//! ```
+
+use crate::logic::BoolOp;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+mod _impl_bdd_manager;
+mod _impl_bdd_set;
+#[cfg(test)]
+mod test_util;
+
+/// A handle of a BDD node stored in a `BddManager`.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Bdd(usize);
+
+/// **(internal)** A decision node: if `variable` is false, continue to `low`, otherwise
+/// continue to `high`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct BddNode {
+    variable: usize,
+    low: Bdd,
+    high: Bdd,
+}
+
+/// Storage of BDD nodes over a fixed number of variables with a fixed variable ordering.
+#[derive(Clone, Debug)]
+pub struct BddManager {
+    /// `ordering[i]` is the variable tested at the `i`-th level (from the top).
+    ordering: Vec<usize>,
+    /// The level of every variable (inverse of `ordering`).
+    levels: Vec<usize>,
+    /// All nodes; the first two are the `false` and `true` terminals.
+    nodes: Vec<BddNode>,
+    /// Unique table: every node is stored only once.
+    unique: HashMap<BddNode, Bdd>,
+    /// Memoized results of binary operations.
+    cache: HashMap<(BoolOp, Bdd, Bdd), Bdd>,
+}
+
+/// A set of `BitVector`s represented symbolically by a `Bdd`. For usage examples, see
+/// module description.
+#[derive(Clone, Debug)]
+pub struct BddSet<BV> {
+    /// `None` only for empty sets created by `Set::empty`.
+    manager: Option<Rc<RefCell<BddManager>>>,
+    root: Bdd,
+    elements: PhantomData<BV>,
+}
+
+/// Iterator over the elements of a `BddSet`. The paths of the BDD are explored lazily and
+/// every path (cube) is expanded into its elements one at a time, so only a few elements
+/// of a very large set can be inspected.
+#[derive(Clone, Debug)]
+pub struct BddSetIterator<BV> {
+    manager: Option<Rc<RefCell<BddManager>>>,
+    /// Unexplored paths: a node together with the partial cube leading to it.
+    stack: Vec<(Bdd, Vec<Option<bool>>)>,
+    /// The free variables of the cube that is being expanded, and its next element.
+    /// Elements are enumerated by counting in binary over the free variables.
+    expanding: Option<(Vec<usize>, BV)>,
+}
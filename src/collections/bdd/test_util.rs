@@ -0,0 +1,9 @@
+//! **(internal)** Helpers shared by the tests of this module.
+
+use crate::logic::BoolExpr;
+
+/// **(internal)** Parse an expression over atoms `x0, x1, ...`, where `x{i}` becomes `i`.
+pub(super) fn expr(input: &str) -> BoolExpr<usize> {
+    let expr: BoolExpr<String> = input.parse().unwrap();
+    return expr.map_atoms(|atom| atom[1..].parse().unwrap());
+}
@@ -1,5 +1,6 @@
 use super::{ArrayBitVector, BitVector};
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 
 /* Not sure why bitvector::BitVector does not implement Eq, but we want to. */
 impl Eq for ArrayBitVector {}
@@ -70,6 +71,16 @@ impl From<Vec<bool>> for ArrayBitVector {
     }
 }
 
+/* Hash is consistent with equality, since equal vectors have the same length and ones. */
+impl Hash for ArrayBitVector {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for i in self.values.iter() {
+            i.hash(state);
+        }
+    }
+}
+
 impl Debug for ArrayBitVector {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(f, "ArrayBitVector({})[", self.len)?;
@@ -91,6 +102,7 @@ impl Debug for ArrayBitVector {
 #[cfg(test)]
 mod tests {
     use crate::collections::bitvectors::{ArrayBitVector, BitVector};
+    use std::collections::HashSet;
 
     #[test]
     fn test_array_bit_vector() {
@@ -116,6 +128,15 @@ mod tests {
         assert!(!bv.get(2));
     }
 
+    #[test]
+    fn test_array_bit_vector_hash() {
+        let mut set = HashSet::new();
+        set.insert(ArrayBitVector::from_ones(100, vec![1, 70]));
+        assert!(set.contains(&ArrayBitVector::from_ones(100, vec![70, 1])));
+        assert!(!set.contains(&ArrayBitVector::from_ones(101, vec![1, 70])));
+        assert!(!set.contains(&ArrayBitVector::from_ones(100, vec![1])));
+    }
+
     #[test]
     #[should_panic]
     #[cfg(shields_up)]
@@ -1,4 +1,5 @@
 use crate::collections::bitvectors::{BitVector, BitVector58};
+use crate::State;
 use std::fmt::{Debug, Display, Formatter};
 
 impl BitVector58 {
@@ -15,6 +16,8 @@ impl BitVector58 {
     }
 }
 
+impl State for BitVector58 {}
+
 impl BitVector for BitVector58 {
     fn max_length() -> usize {
         return 58;
@@ -0,0 +1,27 @@
+use super::{IdVertex, Vertex};
+
+impl Vertex for IdVertex {}
+
+impl From<usize> for IdVertex {
+    fn from(val: usize) -> Self {
+        return IdVertex(val);
+    }
+}
+
+impl From<IdVertex> for usize {
+    fn from(val: IdVertex) -> Self {
+        return val.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::graphs::IdVertex;
+
+    #[test]
+    fn id_vertex_conversions() {
+        let vertex = IdVertex::from(5);
+        assert_eq!(5usize, vertex.into());
+        assert!(IdVertex::from(3) < vertex);
+    }
+}
@@ -30,12 +30,15 @@
 //!
 //! Often, graphs contain more than the basic vertex-edge structure. To avoid having a specialized
 //! trait for every such variant, we consider `EdgeLabels` and `VertexLabels` that facilitate this
-//! extra information.
+//! extra information. For example, `networks::RegulatoryGraph` provides the regulations
+//! between network variables (represented as `IdVertex`s) as `EdgeLabels`.
 
 use crate::collections::bitvectors::BitVector58;
 use std::collections::HashMap;
 use std::hash::Hash;
 
+mod _impl_id_vertex;
+
 /// `EvolutionOperator` is essentially a function $\sigma: A -> 2^B$, i.e. taking an element $s \in A$
 /// and returning a subset $t \subseteq B$. For simplicity, the subset is represented as an
 /// `Iterator` (because it can be often constructed on-the-fly).
@@ -60,7 +63,7 @@ trait EvolutionOperator {
 /// We also require `Copy`, so that we have a unified calling convention (If you have "heavy"
 /// vertices, you can implement a caching container - which you should do anyway to reduce
 /// memory consumption).
-trait Vertex: Clone + Copy + Eq + Hash {}
+pub trait Vertex: Clone + Copy + Eq + Hash {}
 
 /// A possible implementation of a `Vertex` is the `BitVector58` which can hold up-to 58
 /// boolean values.
@@ -92,7 +95,7 @@ trait Graph {
 ///
 /// This also allows algorithms to specify that they only require the labeling, not the graph
 /// itself.
-trait VertexLabels {
+pub trait VertexLabels {
     type Label;
     type Vertex: Vertex;
     fn get(&self, vertex: Self::Vertex) -> Self::Label;
@@ -103,7 +106,7 @@ trait VertexLabels {
 ///
 /// Similar to `VertexLabels`, you usually do not want to implement `EdgeLabels` directly by
 /// a `Graph`, but rather provide them as a separate structure.
-trait EdgeLabels {
+pub trait EdgeLabels {
     type Label;
     type Vertex: Vertex;
     fn get(&self, edge: (Self::Vertex, Self::Vertex)) -> Self::Label;
@@ -113,7 +116,8 @@ trait EdgeLabels {
 ///
 /// This "id" can be often used to access additional data about the vertex, or in general as an
 /// index into other data structures (e.g. `VertexLabels`).
-struct IdVertex(usize);
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct IdVertex(usize);
 
 // TODO: Implement example explicit graph and vertex storage...
 struct HashedVertices<D> {
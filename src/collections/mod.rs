@@ -1,5 +1,6 @@
-//! Common data structures used throughout Biodivine. Right now these are `Set` and `BitVector`.
+//! Common data structures used throughout Biodivine. Right now these are `Set`, `BitVector` and BDDs.
 
+pub mod bdd;
 pub mod bitvectors;
 pub mod graphs;
 pub mod sets;
@@ -3,7 +3,8 @@
 //!
 //! Because Rust currently does not have a `Set` trait (and even if it had, its use case would
 //! probably differ from ours), we introduce our own `Set` trait. As an example implementation,
-//! see `ExplicitSet` which simply delegates to rust `HashSet`.
+//! see `ExplicitSet` which simply delegates to rust `HashSet`. A symbolic implementation for
+//! (possibly huge) sets of bit vectors is `BddSet` in the `collections::bdd` module.
 //!
 //! Basic set-like operations are provided:
 //!
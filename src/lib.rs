@@ -4,6 +4,9 @@ use std::hash::Hash;
 pub mod collections;
 pub mod graph;
 pub mod logic;
+pub mod networks;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 // Old stuff (do not delete until release because people are using it!)
 pub mod param_graph;
@@ -0,0 +1,102 @@
+use super::BoolExpr;
+use crate::collections::sets::ExplicitSet;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+impl<A: Eq + Clone + Debug> BoolExpr<A> {
+    /// Replace every atom of this expression with the expression computed by `f`.
+    pub fn replace_atoms<B, F>(&self, mut f: F) -> BoolExpr<B>
+    where
+        B: Eq + Clone + Debug,
+        F: FnMut(&A) -> BoolExpr<B>,
+    {
+        return self.fold(|node, children| match node {
+            BoolExpr::Atom(atom) => f(atom),
+            _ => node.with_children(children),
+        });
+    }
+
+    /// Create a structurally identical expression with every atom transformed by `f`.
+    pub fn map_atoms<B, F>(&self, mut f: F) -> BoolExpr<B>
+    where
+        B: Eq + Clone + Debug,
+        F: FnMut(&A) -> B,
+    {
+        return self.replace_atoms(|atom| BoolExpr::Atom(f(atom)));
+    }
+
+    /// Replace every occurrence of `atom` in this expression with `expr`.
+    pub fn substitute(&self, atom: &A, expr: &BoolExpr<A>) -> BoolExpr<A> {
+        return self.replace_atoms(|it| {
+            if it == atom {
+                expr.clone()
+            } else {
+                BoolExpr::Atom(it.clone())
+            }
+        });
+    }
+}
+
+impl<A: Eq + Clone + Debug + Hash> BoolExpr<A> {
+    /// The set of atoms this expression syntactically depends on.
+    ///
+    /// Note that the expression does not have to depend on these atoms semantically
+    /// (e.g. in `a | !a`).
+    pub fn support(&self) -> ExplicitSet<A> {
+        let mut result = HashSet::new();
+        self.fold(|node, _: Vec<()>| {
+            if let BoolExpr::Atom(atom) = node {
+                result.insert(atom.clone());
+            }
+        });
+        return ExplicitSet::from(result);
+    }
+
+    /// A vector of distinct atoms appearing in this expression, in the order of their first
+    /// occurrence (from left to right).
+    pub fn atoms(&self) -> Vec<A> {
+        let mut seen = HashSet::new();
+        let mut result: Vec<A> = Vec::new();
+        self.fold(|node, _: Vec<()>| {
+            if let BoolExpr::Atom(atom) = node {
+                if seen.insert(atom.clone()) {
+                    result.push(atom.clone());
+                }
+            }
+        });
+        return result;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::sets::ExplicitSet;
+    use crate::logic::test_util::parse;
+
+    #[test]
+    fn map_and_substitute_atoms() {
+        let expr = parse("a & !(b | true) => c ^ a");
+        let names = ["a", "b", "c"];
+        let indexed = expr.map_atoms(|atom| names.iter().position(|n| n == atom).unwrap());
+        assert_eq!("0 & !(1 | true) => 2 ^ 0", indexed.to_string());
+        assert_eq!(expr, indexed.map_atoms(|i| names[*i].to_string()));
+        let inlined = expr.substitute(&"a".to_string(), &parse("x | y"));
+        assert_eq!("(x | y) & !(b | true) => c ^ (x | y)", inlined.to_string());
+        assert_eq!(expr, expr.substitute(&"z".to_string(), &parse("x")));
+    }
+
+    #[test]
+    fn support_and_atoms() {
+        let expr = parse("c & !(b | true) => c ^ a | a");
+        assert_eq!(vec!["c", "b", "a"], expr.atoms());
+        assert_eq!(
+            ExplicitSet::new_with_items(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+            expr.support()
+        );
+        assert_eq!(
+            ExplicitSet::new_with_items(vec![]),
+            parse("true | false").support()
+        );
+    }
+}
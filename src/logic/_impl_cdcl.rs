@@ -0,0 +1,572 @@
+//! **(internal)** A CDCL (conflict driven clause learning) SAT solver over integer variables.
+//!
+//! Literals are encoded as `usize` values: `2 * variable` is the positive literal and
+//! `2 * variable + 1` is the negative literal, so the negation of a literal is `literal ^ 1`.
+
+/// **(internal)** Number of conflicts in one unit of the Luby restart sequence.
+const RESTART_UNIT: usize = 100;
+
+/// **(internal)** Factor by which the activity increment grows after every conflict
+/// (i.e. the inverse of the activity decay).
+const ACTIVITY_GROWTH: f64 = 1.0 / 0.95;
+
+/// **(internal)** The state of the CDCL search.
+///
+/// Clauses are watched by their first two literals: `watches[l]` contains the indices of
+/// clauses where `l` is one of the first two literals, and these are inspected when `l`
+/// becomes false. The search uses first-UIP clause learning with non-chronological
+/// backtracking, VSIDS-like variable activities with phase saving, and Luby restarts.
+///
+/// Clauses can be added between calls to `solve`, and every call can specify assumptions
+/// (literals which are fixed for that call only). Learned clauses are consequences of the
+/// clauses alone, so they stay valid for all subsequent calls.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Cdcl {
+    clauses: Vec<Vec<usize>>,
+    watches: Vec<Vec<usize>>,
+    values: Vec<Option<bool>>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    /// Assigned literals in the order of assignment.
+    trail: Vec<usize>,
+    /// For every decision level, the length of the trail before the level started.
+    trail_limits: Vec<usize>,
+    /// Position in the trail of the first literal whose consequences were not propagated yet.
+    propagated: usize,
+    activity: Vec<f64>,
+    activity_increment: f64,
+    /// Candidates for branching, ordered by activity. Contains at least all unassigned
+    /// variables.
+    order: VarOrder,
+    /// The last value of every variable (used to pick the polarity of decisions).
+    phases: Vec<bool>,
+    /// True once the clauses are known to be unsatisfiable (regardless of assumptions).
+    inconsistent: bool,
+}
+
+/// **(internal)** A binary max-heap of variables ordered by their activity (ties are broken
+/// in favour of the smaller variable), so that the most active variable can be found
+/// without scanning all variables.
+#[derive(Clone, Debug, Default)]
+struct VarOrder {
+    heap: Vec<usize>,
+    /// Position of every variable in `heap`, if it is there.
+    positions: Vec<Option<usize>>,
+}
+
+impl VarOrder {
+    /// **(internal)** True if `a` should be picked before `b`.
+    fn before(activity: &[f64], a: usize, b: usize) -> bool {
+        return activity[a] > activity[b] || (activity[a] == activity[b] && a < b);
+    }
+
+    /// **(internal)** Add `variable` to the heap (if it is not there already).
+    fn insert(&mut self, variable: usize, activity: &[f64]) {
+        if self.positions.len() <= variable {
+            self.positions.resize(variable + 1, None);
+        }
+        if self.positions[variable].is_none() {
+            self.positions[variable] = Some(self.heap.len());
+            self.heap.push(variable);
+            self.sift_up(self.heap.len() - 1, activity);
+        }
+    }
+
+    /// **(internal)** Restore the heap order after the activity of `variable` increased.
+    fn increased(&mut self, variable: usize, activity: &[f64]) {
+        if let Some(position) = self.positions[variable] {
+            self.sift_up(position, activity);
+        }
+    }
+
+    /// **(internal)** Remove and return the variable with the highest activity.
+    fn pop(&mut self, activity: &[f64]) -> Option<usize> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let top = self.heap.swap_remove(0);
+        self.positions[top] = None;
+        if !self.heap.is_empty() {
+            self.positions[self.heap[0]] = Some(0);
+            self.sift_down(0, activity);
+        }
+        return Some(top);
+    }
+
+    fn sift_up(&mut self, mut position: usize, activity: &[f64]) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if !Self::before(activity, self.heap[position], self.heap[parent]) {
+                break;
+            }
+            self.swap(position, parent);
+            position = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut position: usize, activity: &[f64]) {
+        loop {
+            let mut best = position;
+            for child in [2 * position + 1, 2 * position + 2] {
+                if child < self.heap.len()
+                    && Self::before(activity, self.heap[child], self.heap[best])
+                {
+                    best = child;
+                }
+            }
+            if best == position {
+                return;
+            }
+            self.swap(position, best);
+            position = best;
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.positions[self.heap[a]] = Some(a);
+        self.positions[self.heap[b]] = Some(b);
+    }
+}
+
+/// **(internal)** The variable of a literal.
+fn var(literal: usize) -> usize {
+    return literal >> 1;
+}
+
+/// **(internal)** The positive (`true`) or negative (`false`) literal of a variable.
+pub(super) fn literal(variable: usize, positive: bool) -> usize {
+    return 2 * variable + if positive { 0 } else { 1 };
+}
+
+/// **(internal)** The value of a literal under the given (partial) assignment.
+fn literal_value(values: &[Option<bool>], literal: usize) -> Option<bool> {
+    return values[var(literal)].map(|value| value == (literal & 1 == 0));
+}
+
+/// **(internal)** The `i`-th element (starting from 1) of the Luby sequence 1, 1, 2, 1, 1,
+/// 2, 4, 1, ...
+fn luby(mut i: usize) -> usize {
+    loop {
+        // Find the smallest k such that i <= 2^k - 1.
+        let mut k = 1;
+        while (1 << k) - 1 < i {
+            k += 1;
+        }
+        if (1 << k) - 1 == i {
+            return 1 << (k - 1);
+        }
+        i -= (1 << (k - 1)) - 1;
+    }
+}
+
+impl Cdcl {
+    pub(super) fn new() -> Cdcl {
+        return Cdcl {
+            activity_increment: 1.0,
+            ..Cdcl::default()
+        };
+    }
+
+    pub(super) fn num_vars(&self) -> usize {
+        return self.values.len();
+    }
+
+    /// Create a new variable and return its index.
+    pub(super) fn new_var(&mut self) -> usize {
+        self.watches.push(Vec::new());
+        self.watches.push(Vec::new());
+        self.values.push(None);
+        self.levels.push(0);
+        self.reasons.push(None);
+        self.activity.push(0.0);
+        self.phases.push(false);
+        let variable = self.values.len() - 1;
+        self.order.insert(variable, &self.activity);
+        return variable;
+    }
+
+    /// Value of the given variable in the last model found by `solve`.
+    ///
+    /// Only valid until the solver is modified again.
+    pub(super) fn value(&self, variable: usize) -> bool {
+        return self.phases[variable];
+    }
+
+    /// Add a clause to the solver. Tautologies are ignored, and an empty clause makes
+    /// the solver permanently unsatisfiable.
+    pub(super) fn add_clause(&mut self, mut clause: Vec<usize>) {
+        if self.inconsistent {
+            return;
+        }
+        self.backtrack(0);
+        clause.sort_unstable();
+        clause.dedup();
+        // After sorting, complementary literals are next to each other.
+        if clause.windows(2).any(|pair| pair[0] ^ 1 == pair[1]) {
+            return;
+        }
+        let values = &self.values;
+        if clause
+            .iter()
+            .any(|l| literal_value(values, *l) == Some(true))
+        {
+            return;
+        }
+        clause.retain(|l| literal_value(values, *l).is_none());
+        match clause.len() {
+            0 => self.inconsistent = true,
+            1 => {
+                self.assign(clause[0], None);
+                if self.propagate().is_some() {
+                    self.inconsistent = true;
+                }
+            }
+            _ => {
+                self.attach(clause);
+            }
+        }
+    }
+
+    /// Decide whether the clauses are satisfiable with all `assumptions` being true. If they
+    /// are, the model can be read using `value`.
+    pub(super) fn solve(&mut self, assumptions: &[usize]) -> bool {
+        if self.inconsistent {
+            return false;
+        }
+        self.backtrack(0);
+        let mut restarts = 1;
+        let mut conflict_budget = RESTART_UNIT * luby(restarts);
+        let result = loop {
+            if let Some(conflict) = self.propagate() {
+                if self.trail_limits.is_empty() {
+                    self.inconsistent = true;
+                    break false;
+                }
+                let (learned, level) = self.analyze(conflict);
+                self.backtrack(level);
+                let asserting = learned[0];
+                if learned.len() == 1 {
+                    self.assign(asserting, None);
+                } else {
+                    let reason = self.attach(learned);
+                    self.assign(asserting, Some(reason));
+                }
+                self.activity_increment *= ACTIVITY_GROWTH;
+                conflict_budget = conflict_budget.saturating_sub(1);
+                continue;
+            }
+            if conflict_budget == 0 {
+                restarts += 1;
+                conflict_budget = RESTART_UNIT * luby(restarts);
+                self.backtrack(0);
+                continue;
+            }
+            let level = self.trail_limits.len();
+            if level < assumptions.len() {
+                let assumption = assumptions[level];
+                match literal_value(&self.values, assumption) {
+                    // Already implied, open an empty decision level to keep levels aligned
+                    // with the assumptions.
+                    Some(true) => self.trail_limits.push(self.trail.len()),
+                    Some(false) => break false,
+                    None => {
+                        self.trail_limits.push(self.trail.len());
+                        self.assign(assumption, None);
+                    }
+                }
+                continue;
+            }
+            match self.pick_branch_variable() {
+                None => break true,
+                Some(variable) => {
+                    self.trail_limits.push(self.trail.len());
+                    self.assign(literal(variable, self.phases[variable]), None);
+                }
+            }
+        };
+        if result {
+            // Save the model into the phases (all variables are assigned).
+            for (variable, value) in self.values.iter().enumerate() {
+                self.phases[variable] = value.unwrap();
+            }
+        }
+        self.backtrack(0);
+        return result;
+    }
+
+    /// **(internal)** Add a clause with at least two literals, watching the first two.
+    fn attach(&mut self, clause: Vec<usize>) -> usize {
+        let index = self.clauses.len();
+        self.watches[clause[0]].push(index);
+        self.watches[clause[1]].push(index);
+        self.clauses.push(clause);
+        return index;
+    }
+
+    /// **(internal)** Make `literal` true at the current decision level.
+    fn assign(&mut self, literal: usize, reason: Option<usize>) {
+        let variable = var(literal);
+        self.values[variable] = Some(literal & 1 == 0);
+        self.levels[variable] = self.trail_limits.len();
+        self.reasons[variable] = reason;
+        self.trail.push(literal);
+    }
+
+    /// **(internal)** Undo all assignments above the given decision level.
+    fn backtrack(&mut self, level: usize) {
+        if self.trail_limits.len() <= level {
+            return;
+        }
+        let limit = self.trail_limits[level];
+        for literal in self.trail.drain(limit..) {
+            let variable = var(literal);
+            self.phases[variable] = self.values[variable].unwrap();
+            self.values[variable] = None;
+            self.reasons[variable] = None;
+            self.order.insert(variable, &self.activity);
+        }
+        self.trail_limits.truncate(level);
+        self.propagated = self.trail.len();
+    }
+
+    /// **(internal)** Unit propagation over watched literals. Returns the index of a
+    /// conflicting clause (one with all literals false) if there is one.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let false_literal = self.trail[self.propagated] ^ 1;
+            self.propagated += 1;
+            let watching = std::mem::take(&mut self.watches[false_literal]);
+            let mut kept = Vec::with_capacity(watching.len());
+            let mut conflict = None;
+            for index in watching {
+                if conflict.is_some() {
+                    kept.push(index);
+                    continue;
+                }
+                let clause = &mut self.clauses[index];
+                // Make sure the false literal is the second one.
+                if clause[0] == false_literal {
+                    clause.swap(0, 1);
+                }
+                let first = clause[0];
+                if literal_value(&self.values, first) == Some(true) {
+                    kept.push(index);
+                    continue;
+                }
+                let values = &self.values;
+                let replacement =
+                    (2..clause.len()).find(|k| literal_value(values, clause[*k]) != Some(false));
+                if let Some(k) = replacement {
+                    clause.swap(1, k);
+                    self.watches[clause[1]].push(index);
+                    continue;
+                }
+                kept.push(index);
+                if literal_value(&self.values, first) == Some(false) {
+                    conflict = Some(index);
+                } else {
+                    self.assign(first, Some(index));
+                }
+            }
+            self.watches[false_literal] = kept;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        return None;
+    }
+
+    /// **(internal)** First-UIP conflict analysis. Returns the learned clause (with the
+    /// asserting literal first and a literal of the highest remaining level second) and the
+    /// level to backtrack to.
+    fn analyze(&mut self, conflict: usize) -> (Vec<usize>, usize) {
+        let current_level = self.trail_limits.len();
+        let mut seen = vec![false; self.num_vars()];
+        let mut learned = vec![0];
+        // Number of seen literals of the current level which were not resolved yet.
+        let mut pending = 0;
+        let mut clause = conflict;
+        let mut position = self.trail.len();
+        loop {
+            // The first literal of a reason clause is the one it implied, which is skipped
+            // (it is the literal being resolved on). The conflict clause is used whole.
+            let skip = if clause == conflict { 0 } else { 1 };
+            for k in skip..self.clauses[clause].len() {
+                let variable = var(self.clauses[clause][k]);
+                if !seen[variable] && self.levels[variable] > 0 {
+                    seen[variable] = true;
+                    self.bump(variable);
+                    if self.levels[variable] == current_level {
+                        pending += 1;
+                    } else {
+                        learned.push(self.clauses[clause][k]);
+                    }
+                }
+            }
+            // Find the most recently assigned seen literal.
+            loop {
+                position -= 1;
+                if seen[var(self.trail[position])] {
+                    break;
+                }
+            }
+            let literal = self.trail[position];
+            seen[var(literal)] = false;
+            pending -= 1;
+            if pending == 0 {
+                learned[0] = literal ^ 1;
+                break;
+            }
+            clause = self.reasons[var(literal)].unwrap();
+        }
+        let mut level = 0;
+        for k in 1..learned.len() {
+            if self.levels[var(learned[k])] > level {
+                level = self.levels[var(learned[k])];
+                learned.swap(1, k);
+            }
+        }
+        return (learned, level);
+    }
+
+    /// **(internal)** Increase the activity of a variable involved in a conflict.
+    fn bump(&mut self, variable: usize) {
+        self.activity[variable] += self.activity_increment;
+        if self.activity[variable] > 1e100 {
+            for activity in self.activity.iter_mut() {
+                *activity *= 1e-100;
+            }
+            self.activity_increment *= 1e-100;
+        }
+        // Uniform rescaling preserves the order, so only the bumped variable can move.
+        self.order.increased(variable, &self.activity);
+    }
+
+    /// **(internal)** The unassigned variable with the highest activity, if any.
+    ///
+    /// Assigned variables popped from the order are inserted back once they are unassigned
+    /// by `backtrack`.
+    fn pick_branch_variable(&mut self) -> Option<usize> {
+        while let Some(variable) = self.order.pop(&self.activity) {
+            if self.values[variable].is_none() {
+                return Some(variable);
+            }
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{literal, luby, Cdcl, VarOrder};
+
+    /// Clauses stating that `pigeons` pigeons sit in `holes` holes, each hole holding at most
+    /// one pigeon. Variable `p * holes + h` means pigeon `p` sits in hole `h`.
+    fn pigeonhole(solver: &mut Cdcl, pigeons: usize, holes: usize) {
+        for _ in 0..(pigeons * holes) {
+            solver.new_var();
+        }
+        for p in 0..pigeons {
+            solver.add_clause((0..holes).map(|h| literal(p * holes + h, true)).collect());
+        }
+        for h in 0..holes {
+            for p in 0..pigeons {
+                for q in (p + 1)..pigeons {
+                    let (a, b) = (p * holes + h, q * holes + h);
+                    solver.add_clause(vec![literal(a, false), literal(b, false)]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cdcl_luby_sequence() {
+        let sequence: Vec<usize> = (1..16).map(luby).collect();
+        assert_eq!(vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8], sequence);
+    }
+
+    #[test]
+    fn cdcl_variable_order() {
+        let mut activity = vec![0.0, 3.0, 1.0, 3.0, 2.0];
+        let mut order = VarOrder::default();
+        for variable in 0..activity.len() {
+            order.insert(variable, &activity);
+        }
+        order.insert(1, &activity);
+        activity[0] = 2.5;
+        order.increased(0, &activity);
+        let popped: Vec<usize> = std::iter::from_fn(|| order.pop(&activity)).collect();
+        assert_eq!(vec![1, 3, 0, 4, 2], popped);
+    }
+
+    #[test]
+    fn cdcl_pigeonhole() {
+        let mut solver = Cdcl::new();
+        pigeonhole(&mut solver, 6, 6);
+        assert!(solver.solve(&[]));
+        for h in 0..6 {
+            let pigeons = (0..6).filter(|p| solver.value(p * 6 + h)).count();
+            assert!(pigeons <= 1);
+        }
+        let mut solver = Cdcl::new();
+        pigeonhole(&mut solver, 7, 6);
+        assert!(!solver.solve(&[]));
+        assert!(!solver.solve(&[]));
+    }
+
+    #[test]
+    fn cdcl_assumptions_and_increments() {
+        let mut solver = Cdcl::new();
+        let (a, b, c) = (solver.new_var(), solver.new_var(), solver.new_var());
+        solver.add_clause(vec![literal(a, false), literal(b, true)]);
+        solver.add_clause(vec![literal(b, false), literal(c, true)]);
+        assert!(solver.solve(&[literal(a, true)]));
+        assert!(solver.value(b) && solver.value(c));
+        assert!(!solver.solve(&[literal(a, true), literal(c, false)]));
+        // Failed assumptions do not make the solver inconsistent.
+        assert!(solver.solve(&[literal(c, false)]));
+        assert!(!solver.value(a));
+        solver.add_clause(vec![literal(a, true)]);
+        assert!(!solver.solve(&[literal(c, false)]));
+        assert!(solver.solve(&[]));
+        solver.add_clause(vec![literal(c, false)]);
+        assert!(!solver.solve(&[]));
+    }
+
+    #[test]
+    fn cdcl_random_instances() {
+        // Compare with exhaustive search on random 3-CNF instances around the phase
+        // transition (a simple LCG is enough to generate them).
+        let mut seed: u64 = 42;
+        let mut next = |bound: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            return ((seed >> 33) as usize) % bound;
+        };
+        let vars = 10;
+        for _ in 0..200 {
+            let clauses: Vec<Vec<usize>> = (0..43)
+                .map(|_| (0..3).map(|_| literal(next(vars), next(2) == 0)).collect())
+                .collect();
+            let satisfies = |valuation: usize, clause: &Vec<usize>| {
+                let value = |l: usize| (valuation >> (l >> 1)) & 1 == (1 - (l & 1));
+                return clause.iter().any(|l| value(*l));
+            };
+            let expected = (0..(1 << vars)).any(|v| clauses.iter().all(|c| satisfies(v, c)));
+            let mut solver = Cdcl::new();
+            for _ in 0..vars {
+                solver.new_var();
+            }
+            for clause in &clauses {
+                solver.add_clause(clause.clone());
+            }
+            assert_eq!(expected, solver.solve(&[]));
+            if expected {
+                let model = (0..vars).fold(0, |v, x| v | ((solver.value(x) as usize) << x));
+                assert!(clauses.iter().all(|c| satisfies(model, c)));
+            }
+        }
+    }
+}
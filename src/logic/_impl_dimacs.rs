@@ -0,0 +1,278 @@
+use super::{BoolExpr, ParseError};
+use crate::util::build_index_map;
+use std::collections::HashMap;
+use std::fmt::{Debug, Display, Write};
+use std::hash::Hash;
+
+impl<A: Eq + Clone + Debug + Hash + Display> BoolExpr<A> {
+    /// Write this expression in the DIMACS CNF format.
+    ///
+    /// Atoms are numbered from `1` in the order of their first occurrence (see `atoms`). The
+    /// output starts with a comment line `c var <index> <atom>` for every atom, followed by
+    /// the problem line and the clauses (one per line).
+    ///
+    /// Panics if the expression is not in CNF (see `is_cnf`); use `to_cnf` or
+    /// `to_cnf_tseitin` to convert it first.
+    pub fn to_dimacs(&self) -> String {
+        let clauses = match self {
+            BoolExpr::And(clauses) if self.is_cnf() => clauses,
+            _ => panic!("Only expressions in CNF can be written as DIMACS."),
+        };
+        let atoms = self.atoms();
+        let index = build_index_map(&atoms, |_, i| i + 1);
+        let mut result = String::new();
+        for (i, atom) in atoms.iter().enumerate() {
+            writeln!(result, "c var {} {}", i + 1, atom).unwrap();
+        }
+        writeln!(result, "p cnf {} {}", atoms.len(), clauses.len()).unwrap();
+        for clause in clauses {
+            for literal in clause.children() {
+                let (atom, positive) = literal.as_literal().unwrap();
+                let sign = if positive { "" } else { "-" };
+                write!(result, "{}{} ", sign, index[atom]).unwrap();
+            }
+            writeln!(result, "0").unwrap();
+        }
+        return result;
+    }
+}
+
+impl BoolExpr<usize> {
+    /// Read a CNF expression in the DIMACS format. DIMACS variable `v` becomes the atom `v - 1`
+    /// (so that atoms can be used as indices, e.g. in `eval_bit_vector`).
+    ///
+    /// The result is always an `And` of `Or` clauses. Clauses can span multiple lines and the
+    /// terminating `0` of the last clause can be omitted. A line starting with `%` (used by
+    /// some benchmark sets) ends the input.
+    pub fn from_dimacs(input: &str) -> Result<BoolExpr<usize>, ParseError> {
+        return BoolExpr::from_dimacs_with_names(input).map(|(expr, _)| expr);
+    }
+
+    /// Same as `from_dimacs`, but also returns the names of atoms given in comment lines of
+    /// the form `c var <variable> <name>` (as produced by `to_dimacs`), where the name is the
+    /// rest of the line (without surrounding whitespace). Other comments are ignored.
+    pub fn from_dimacs_with_names(
+        input: &str,
+    ) -> Result<(BoolExpr<usize>, HashMap<usize, String>), ParseError> {
+        let mut header: Option<(usize, usize)> = None;
+        let mut names = HashMap::new();
+        let mut clauses: Vec<BoolExpr<usize>> = Vec::new();
+        let mut clause: Vec<BoolExpr<usize>> = Vec::new();
+        let mut last_line = 0;
+        for (line_index, line) in input.lines().enumerate() {
+            let line_number = line_index + 1;
+            last_line = line_number;
+            let tokens = tokens(line);
+            let error = |column: usize, expected: &str, found: &str| ParseError {
+                line: line_number,
+                column,
+                expected: expected.to_string(),
+                found: found.to_string(),
+            };
+            match tokens.first() {
+                None => continue,
+                Some((_, "%")) => break,
+                Some((_, "c")) => {
+                    if let Some((variable, name)) = name_comment(line) {
+                        names.insert(variable, name.to_string());
+                    }
+                }
+                Some((column, "p")) => {
+                    if header.is_some() {
+                        return Err(error(
+                            column + 1,
+                            "clause or comment",
+                            "second problem line",
+                        ));
+                    }
+                    if tokens.get(1).map(|(_, format)| *format) != Some("cnf") {
+                        let (column, found) = tokens.get(1).cloned().unwrap_or((line.len(), ""));
+                        return Err(error(column + 1, "`cnf`", &describe(found)));
+                    }
+                    let mut numbers = Vec::new();
+                    for k in 2..4 {
+                        let (column, token) = tokens.get(k).cloned().unwrap_or((line.len(), ""));
+                        let number = token.parse::<usize>().map_err(|_| {
+                            error(column + 1, "a non-negative number", &describe(token))
+                        })?;
+                        numbers.push(number);
+                    }
+                    if let Some((column, token)) = tokens.get(4) {
+                        return Err(error(column + 1, "end of line", &describe(token)));
+                    }
+                    header = Some((numbers[0], numbers[1]));
+                }
+                Some((column, _)) if header.is_none() => {
+                    return Err(error(column + 1, "problem line", "a clause"));
+                }
+                Some(_) => {
+                    let variables = header.unwrap().0;
+                    for (column, token) in tokens {
+                        let literal = token.parse::<i64>().map_err(|_| {
+                            error(column + 1, "an integer literal", &describe(token))
+                        })?;
+                        let variable = literal.unsigned_abs() as usize;
+                        if literal == 0 {
+                            clauses.push(BoolExpr::Or(std::mem::take(&mut clause)));
+                        } else if variable > variables {
+                            let expected = format!("a variable between 1 and {}", variables);
+                            return Err(error(column + 1, &expected, &describe(token)));
+                        } else if literal > 0 {
+                            clause.push(BoolExpr::Atom(variable - 1));
+                        } else {
+                            clause.push(BoolExpr::Not(Box::new(BoolExpr::Atom(variable - 1))));
+                        }
+                    }
+                }
+            }
+        }
+        if !clause.is_empty() {
+            clauses.push(BoolExpr::Or(clause));
+        }
+        let error = |expected: String, found: String| ParseError {
+            line: last_line,
+            column: 1,
+            expected,
+            found,
+        };
+        return match header {
+            None => Err(error(
+                "problem line".to_string(),
+                "end of input".to_string(),
+            )),
+            Some((_, count)) if count != clauses.len() => Err(error(
+                format!("{} clauses", count),
+                format!("{} clauses", clauses.len()),
+            )),
+            Some(_) => Ok((BoolExpr::And(clauses), names)),
+        };
+    }
+}
+
+/// **(internal)** Split a line into whitespace separated tokens together with their
+/// (zero-based) column.
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut result = Vec::new();
+    let mut start = None;
+    for (i, c) in line
+        .char_indices()
+        .chain(std::iter::once((line.len(), ' ')))
+    {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                result.push((line[..s].chars().count(), &line[s..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    return result;
+}
+
+/// **(internal)** Read the atom and its name from a `c var <variable> <name>` comment line.
+fn name_comment(line: &str) -> Option<(usize, &str)> {
+    let rest = line
+        .trim()
+        .strip_prefix('c')?
+        .trim_start()
+        .strip_prefix("var")?;
+    let (variable, name) = rest.trim_start().split_once(char::is_whitespace)?;
+    let variable = variable.parse::<usize>().ok().filter(|it| *it > 0)?;
+    let name = name.trim();
+    return if rest.starts_with(char::is_whitespace) && !name.is_empty() {
+        Some((variable - 1, name))
+    } else {
+        None
+    };
+}
+
+/// **(internal)** Description of a token for error messages.
+fn describe(token: &str) -> String {
+    return if token.is_empty() {
+        "end of line".to_string()
+    } else {
+        format!("`{}`", token)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::test_util::parse;
+    use crate::logic::{BoolExpr, ParseError};
+
+    fn parse_error(input: &str) -> ParseError {
+        return BoolExpr::from_dimacs(input).unwrap_err();
+    }
+
+    #[test]
+    fn dimacs_write() {
+        let expr = parse("(a | !b) & (!c | b | a) & !a").to_cnf();
+        let expected = "c var 1 a\nc var 2 b\nc var 3 c\np cnf 3 3\n1 -2 0\n-3 2 1 0\n-1 0\n";
+        assert_eq!(expected, expr.to_dimacs());
+        assert_eq!("p cnf 0 0\n", BoolExpr::<String>::And(vec![]).to_dimacs());
+    }
+
+    #[test]
+    #[should_panic]
+    fn dimacs_write_not_cnf() {
+        parse("a | b & c").to_dimacs();
+    }
+
+    #[test]
+    fn dimacs_round_trip() {
+        let expr = parse("(a <=> b) ^ (c => !a)").to_cnf();
+        let (read, names) = BoolExpr::from_dimacs_with_names(&expr.to_dimacs()).unwrap();
+        assert_eq!(expr, read.map_atoms(|atom| names[atom].clone()));
+        assert!(expr.is_equivalent(&read.map_atoms(|atom| names[atom].clone())));
+    }
+
+    #[test]
+    fn dimacs_read_names() {
+        let input = "c 1 clause follows
+c var 1 first atom \nc var 2\nc variable 3 x\n\
+            c var 0 zero\nc  var  3  y\np cnf 3 1\n1 -2 3 0\n";
+        let (_, names) = BoolExpr::from_dimacs_with_names(input).unwrap();
+        assert_eq!(2, names.len());
+        assert_eq!("first atom", names[&0]);
+        assert_eq!("y", names[&2]);
+    }
+
+    #[test]
+    fn dimacs_read() {
+        let input = "c A benchmark.\nc\np cnf 4 3\n 1 -3\n 4 0 -2\n0 3 2 -1\n%\n0\n";
+        let expr = BoolExpr::from_dimacs(input).unwrap();
+        assert_eq!("(x0 | !x2 | x3) & !x1 & (x2 | x1 | !x0)", {
+            expr.map_atoms(|atom| format!("x{}", atom)).to_string()
+        });
+        let empty = BoolExpr::from_dimacs("p cnf 3 1\n0\n").unwrap();
+        assert!(!empty.is_satisfiable());
+    }
+
+    #[test]
+    fn dimacs_errors() {
+        let error = parse_error("c comment\n1 2 0\n");
+        assert_eq!(
+            (2, 1, "problem line"),
+            (error.line, error.column, &*error.expected)
+        );
+        let error = parse_error("p cnf 2 1\n1 x 0\n");
+        assert_eq!((2, 3, "`x`"), (error.line, error.column, &*error.found));
+        let error = parse_error("p cnf 2 1\n1 -3 0\n");
+        assert_eq!("a variable between 1 and 2", error.expected);
+        let error = parse_error("p dnf 2 1\n");
+        assert_eq!((1, 3, "`dnf`"), (error.line, error.column, &*error.found));
+        let error = parse_error("p cnf 2\n");
+        assert_eq!(
+            (1, 8, "end of line"),
+            (error.line, error.column, &*error.found)
+        );
+        let error = parse_error("p cnf 2 2\n1 0\n\n");
+        assert_eq!(3, error.line);
+        assert_eq!(
+            ("2 clauses", "1 clauses"),
+            (&*error.expected, &*error.found)
+        );
+        assert_eq!("end of input", parse_error("c nothing\n").found);
+    }
+}
@@ -0,0 +1,289 @@
+use super::_impl_parser::PRECEDENCE;
+use super::{BoolExpr, BoolExprPrinter, BoolOp, Syntax};
+use std::fmt::{Debug, Display, Error, Formatter};
+
+impl<A: Eq + Clone + Debug> BoolExpr<A> {
+    /// Create a `Display` wrapper which prints this expression using the given `Syntax`.
+    pub fn printer(&self, syntax: Syntax) -> BoolExprPrinter<'_, A> {
+        return BoolExprPrinter { expr: self, syntax };
+    }
+}
+
+impl<A: Eq + Clone + Debug + Display> Display for BoolExpr<A> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        return write_expr(f, self, Syntax::Symbolic);
+    }
+}
+
+impl<A: Eq + Clone + Debug + Display> Display for BoolExprPrinter<'_, A> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        return write_expr(f, self.expr, self.syntax);
+    }
+}
+
+impl Syntax {
+    /// **(internal)** Prefix used to write negation (including trailing whitespace if needed).
+    fn not_symbol(self) -> &'static str {
+        return match self {
+            Syntax::Symbolic => "!",
+            Syntax::Words => "not ",
+            Syntax::Latex => "\\neg ",
+        };
+    }
+
+    /// **(internal)** Symbol used to write the given constant.
+    fn const_symbol(self, value: bool) -> &'static str {
+        return match (self, value) {
+            (Syntax::Latex, true) => "\\top",
+            (Syntax::Latex, false) => "\\bot",
+            (_, true) => "true",
+            (_, false) => "false",
+        };
+    }
+
+    /// **(internal)** Infix symbol used to write the given binary operator.
+    fn op_symbol(self, op: BoolOp) -> &'static str {
+        return match (self, op) {
+            (Syntax::Symbolic, BoolOp::And) => "&",
+            (Syntax::Symbolic, BoolOp::Or) => "|",
+            (Syntax::Symbolic, BoolOp::Xor) => "^",
+            (Syntax::Symbolic, BoolOp::Imp) => "=>",
+            (Syntax::Symbolic, BoolOp::Iff) => "<=>",
+            (Syntax::Words, BoolOp::And) => "and",
+            (Syntax::Words, BoolOp::Or) => "or",
+            (Syntax::Words, BoolOp::Xor) => "xor",
+            (Syntax::Words, BoolOp::Imp) => "implies",
+            (Syntax::Words, BoolOp::Iff) => "iff",
+            (Syntax::Latex, BoolOp::And) => "\\land",
+            (Syntax::Latex, BoolOp::Or) => "\\lor",
+            (Syntax::Latex, BoolOp::Xor) => "\\oplus",
+            (Syntax::Latex, BoolOp::Imp) => "\\Rightarrow",
+            (Syntax::Latex, BoolOp::Iff) => "\\Leftrightarrow",
+        };
+    }
+}
+
+/// **(internal)** Binding strength of the top-level operator of the expression (higher binds
+/// tighter). Consistent with the precedence used by the parser.
+///
+/// N-ary operators with less than two arguments are printed as constants or as the single
+/// argument, so they don't have the precedence of the operator.
+fn precedence<A: Eq + Clone + Debug>(expr: &BoolExpr<A>) -> usize {
+    let mut expr = expr;
+    loop {
+        match expr {
+            BoolExpr::Op { op, .. } => return op_precedence(*op),
+            BoolExpr::And(args) | BoolExpr::Or(args) if args.len() == 1 => expr = &args[0],
+            BoolExpr::And(args) if args.len() > 1 => return op_precedence(BoolOp::And),
+            BoolExpr::Or(args) if args.len() > 1 => return op_precedence(BoolOp::Or),
+            _ => return PRECEDENCE.len(),
+        }
+    }
+}
+
+fn op_precedence(op: BoolOp) -> usize {
+    return PRECEDENCE.iter().position(|it| *it == op).unwrap();
+}
+
+/// **(internal)** A pending step of the non-recursive printing.
+enum Print<'a, A: Eq + Clone + Debug> {
+    /// Print an expression, optionally enclosed in parentheses.
+    Expr(&'a BoolExpr<A>, bool),
+    /// Print a binary operator (including the surrounding whitespace).
+    Op(BoolOp),
+    Text(&'static str),
+}
+
+fn write_expr<A: Eq + Clone + Debug + Display>(
+    f: &mut Formatter,
+    expr: &BoolExpr<A>,
+    syntax: Syntax,
+) -> Result<(), Error> {
+    // Steps are pushed to the stack in reverse order of printing.
+    let mut stack = vec![Print::Expr(expr, false)];
+    while let Some(step) = stack.pop() {
+        match step {
+            Print::Text(text) => write!(f, "{}", text)?,
+            Print::Op(op) => write!(f, " {} ", syntax.op_symbol(op))?,
+            Print::Expr(expr, true) => {
+                stack.push(Print::Text(")"));
+                stack.push(Print::Expr(expr, false));
+                stack.push(Print::Text("("));
+            }
+            Print::Expr(expr, false) => match expr {
+                BoolExpr::Const(value) => write!(f, "{}", syntax.const_symbol(*value))?,
+                BoolExpr::Atom(atom) => write!(f, "{}", atom)?,
+                BoolExpr::Not(inner) => {
+                    write!(f, "{}", syntax.not_symbol())?;
+                    stack.push(Print::Expr(inner, precedence(inner) < PRECEDENCE.len()));
+                }
+                BoolExpr::Op { op, left, right } => {
+                    // Parentheses are needed when the child binds weaker, or when it binds
+                    // equally but sits on the side opposite to the associativity of `op`.
+                    let level = precedence(expr);
+                    let (left_level, right_level) = (precedence(left), precedence(right));
+                    let right_assoc = *op == BoolOp::Imp;
+                    let left_parens = left_level < level || (left_level == level && right_assoc);
+                    let right_parens =
+                        right_level < level || (right_level == level && !right_assoc);
+                    stack.push(Print::Expr(right, right_parens));
+                    stack.push(Print::Op(*op));
+                    stack.push(Print::Expr(left, left_parens));
+                }
+                BoolExpr::And(args) => push_nary(&mut stack, BoolOp::And, args, syntax),
+                BoolExpr::Or(args) => push_nary(&mut stack, BoolOp::Or, args, syntax),
+            },
+        }
+    }
+    return Ok(());
+}
+
+/// **(internal)** Schedule printing of an n-ary operator as a chain of left-associative
+/// binary operators.
+fn push_nary<'a, A: Eq + Clone + Debug>(
+    stack: &mut Vec<Print<'a, A>>,
+    op: BoolOp,
+    args: &'a [BoolExpr<A>],
+    syntax: Syntax,
+) {
+    if args.is_empty() {
+        // Neutral elements of the respective operators.
+        stack.push(Print::Text(syntax.const_symbol(op == BoolOp::And)));
+        return;
+    }
+    let level = if args.len() == 1 {
+        precedence(&args[0])
+    } else {
+        op_precedence(op)
+    };
+    for (i, arg) in args.iter().enumerate().rev() {
+        let arg_level = precedence(arg);
+        stack.push(Print::Expr(
+            arg,
+            arg_level < level || (arg_level == level && i > 0),
+        ));
+        if i > 0 {
+            stack.push(Print::Op(op));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::test_util::op;
+    use crate::logic::{BoolExpr, BoolOp, Syntax};
+
+    const OPERATORS: [BoolOp; 5] = [
+        BoolOp::And,
+        BoolOp::Or,
+        BoolOp::Xor,
+        BoolOp::Imp,
+        BoolOp::Iff,
+    ];
+
+    #[test]
+    fn display_minimal_parentheses() {
+        let cases = vec![
+            ("a & b & c", "a & b & c"),
+            ("a & (b & c)", "a & (b & c)"),
+            ("(a => b) => c", "(a => b) => c"),
+            ("a => (b => c)", "a => b => c"),
+            ("((a | b)) & !(!c)", "(a | b) & !!c"),
+            ("(a & b) ^ (c | d)", "a & b ^ (c | d)"),
+            ("a <=> (b <=> c)", "a <=> (b <=> c)"),
+            ("!(a => b) | c", "!(a => b) | c"),
+        ];
+        for (input, expected) in cases {
+            let expr: BoolExpr<String> = input.parse().unwrap();
+            assert_eq!(expected, expr.to_string());
+        }
+    }
+
+    #[test]
+    fn display_constants_and_nary() {
+        let (a, b) = (BoolExpr::Atom("a"), BoolExpr::Atom("b"));
+        let or = BoolExpr::Or(vec![a.clone(), BoolExpr::Const(false)]);
+        let expr = BoolExpr::And(vec![
+            a.clone(),
+            or.clone(),
+            BoolExpr::Not(Box::new(b.clone())),
+        ]);
+        assert_eq!("a & (a | false) & !b", expr.to_string());
+        assert_eq!(
+            "a \\land (a \\lor \\bot) \\land \\neg b",
+            expr.printer(Syntax::Latex).to_string()
+        );
+        let expr = BoolExpr::And(vec![
+            BoolExpr::And(vec![a.clone(), b.clone()]),
+            a.clone(),
+            BoolExpr::And(vec![b.clone(), a.clone()]),
+        ]);
+        assert_eq!("a & b & a & (b & a)", expr.to_string());
+        assert_eq!("true", BoolExpr::<&str>::And(vec![]).to_string());
+        assert_eq!(
+            "!false",
+            BoolExpr::<&str>::Not(Box::new(BoolExpr::Or(vec![]))).to_string()
+        );
+        let single = BoolExpr::Or(vec![BoolExpr::And(vec![a.clone(), b.clone()])]);
+        assert_eq!(
+            "!(a & b)",
+            BoolExpr::Not(Box::new(single.clone())).to_string()
+        );
+        assert_eq!(
+            "a & b & b",
+            BoolExpr::And(vec![single, b.clone()]).to_string()
+        );
+    }
+
+    #[test]
+    fn display_deep_expression() {
+        let mut expr = BoolExpr::Atom(0);
+        for i in 0..100_000 {
+            expr = BoolExpr::Op {
+                op: BoolOp::Or,
+                left: Box::new(BoolExpr::Not(Box::new(expr))),
+                right: Box::new(BoolExpr::Atom(i)),
+            };
+        }
+        assert!(expr.to_string().starts_with("!(!(!("));
+    }
+
+    #[test]
+    fn display_other_syntax() {
+        let expr: BoolExpr<String> = "!a => (b <=> c)".parse().unwrap();
+        assert_eq!(
+            "!a => (b <=> c)",
+            expr.printer(Syntax::Symbolic).to_string()
+        );
+        assert_eq!(
+            "not a implies (b iff c)",
+            expr.printer(Syntax::Words).to_string()
+        );
+        assert_eq!(
+            "\\neg a \\Rightarrow (b \\Leftrightarrow c)",
+            expr.printer(Syntax::Latex).to_string()
+        );
+    }
+
+    #[test]
+    fn display_round_trip() {
+        // All expressions of depth at most two over an atom and a constant (with negations).
+        let mut base = vec![BoolExpr::Atom("a".to_string()), BoolExpr::Const(true)];
+        for _ in 0..2 {
+            let mut next = base.clone();
+            for left in &base {
+                next.push(BoolExpr::Not(Box::new(left.clone())));
+                for right in &base {
+                    for operator in OPERATORS.iter() {
+                        next.push(op(*operator, left.clone(), right.clone()));
+                    }
+                }
+            }
+            base = next;
+        }
+        for expr in base {
+            let printed = expr.to_string();
+            assert_eq!(expr, printed.parse().unwrap(), "Round trip of {}", printed);
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use super::{BoolExpr, BoolOp, Kleene};
+use crate::collections::bitvectors::BitVector;
+use crate::IdState;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::Not;
+
+/// **(internal)** A pending step of the non-recursive evaluation.
+enum Task<'a, A: Eq + Clone + Debug> {
+    Visit(&'a BoolExpr<A>),
+    Not,
+    Apply(BoolOp),
+    /// Conjunction (`true`) or disjunction (`false`) of the given number of values.
+    Fold(bool, usize),
+}
+
+impl BoolOp {
+    /// Compute the result of this operation for the given operand values.
+    pub fn apply(self, left: bool, right: bool) -> bool {
+        return match self {
+            BoolOp::And => left && right,
+            BoolOp::Or => left || right,
+            BoolOp::Xor => left ^ right,
+            BoolOp::Iff => left == right,
+            BoolOp::Imp => !left || right,
+        };
+    }
+
+    /// Compute the result of this operation in Kleene's three-valued logic. The result is
+    /// `Unknown` only if it depends on the value of an `Unknown` operand.
+    pub fn apply_kleene(self, left: Kleene, right: Kleene) -> Kleene {
+        if let (Some(left), Some(right)) = (left.to_bool(), right.to_bool()) {
+            return Kleene::from(self.apply(left, right));
+        }
+        return match self {
+            BoolOp::And => left.and(right),
+            BoolOp::Or => left.or(right),
+            BoolOp::Imp => (!left).or(right),
+            BoolOp::Xor | BoolOp::Iff => Kleene::Unknown,
+        };
+    }
+}
+
+impl Kleene {
+    /// The value as a `bool`, or `None` if it is `Unknown`.
+    pub fn to_bool(self) -> Option<bool> {
+        return match self {
+            Kleene::False => Some(false),
+            Kleene::True => Some(true),
+            Kleene::Unknown => None,
+        };
+    }
+
+    /// Kleene conjunction: `False` if any operand is `False`, otherwise `Unknown` if any
+    /// operand is `Unknown`.
+    pub fn and(self, other: Kleene) -> Kleene {
+        return match (self, other) {
+            (Kleene::False, _) | (_, Kleene::False) => Kleene::False,
+            (Kleene::True, Kleene::True) => Kleene::True,
+            _ => Kleene::Unknown,
+        };
+    }
+
+    /// Kleene disjunction: `True` if any operand is `True`, otherwise `Unknown` if any
+    /// operand is `Unknown`.
+    pub fn or(self, other: Kleene) -> Kleene {
+        return !(!self).and(!other);
+    }
+}
+
+impl Not for Kleene {
+    type Output = Kleene;
+
+    fn not(self) -> Kleene {
+        return match self {
+            Kleene::False => Kleene::True,
+            Kleene::True => Kleene::False,
+            Kleene::Unknown => Kleene::Unknown,
+        };
+    }
+}
+
+impl From<bool> for Kleene {
+    fn from(value: bool) -> Self {
+        return if value { Kleene::True } else { Kleene::False };
+    }
+}
+
+impl From<Option<bool>> for Kleene {
+    fn from(value: Option<bool>) -> Self {
+        return value.map(Kleene::from).unwrap_or(Kleene::Unknown);
+    }
+}
+
+impl<A: Eq + Clone + Debug> BoolExpr<A> {
+    /// Evaluate this expression, using `valuation` to obtain the values of atoms.
+    ///
+    /// The evaluation uses an explicit stack, so it works for arbitrarily deep expressions.
+    pub fn eval<F>(&self, valuation: F) -> bool
+    where
+        F: Fn(&A) -> bool,
+    {
+        let mut tasks = vec![Task::Visit(self)];
+        let mut values: Vec<bool> = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(BoolExpr::Const(value)) => values.push(*value),
+                Task::Visit(BoolExpr::Atom(atom)) => values.push(valuation(atom)),
+                Task::Visit(BoolExpr::Not(inner)) => {
+                    tasks.push(Task::Not);
+                    tasks.push(Task::Visit(inner));
+                }
+                Task::Visit(BoolExpr::Op { op, left, right }) => {
+                    // Left operand is evaluated first, so it ends up deeper on the value stack.
+                    tasks.push(Task::Apply(*op));
+                    tasks.push(Task::Visit(right));
+                    tasks.push(Task::Visit(left));
+                }
+                Task::Visit(BoolExpr::And(args)) => {
+                    tasks.push(Task::Fold(true, args.len()));
+                    tasks.extend(args.iter().map(Task::Visit));
+                }
+                Task::Visit(BoolExpr::Or(args)) => {
+                    tasks.push(Task::Fold(false, args.len()));
+                    tasks.extend(args.iter().map(Task::Visit));
+                }
+                Task::Not => {
+                    let value = values.pop().unwrap();
+                    values.push(!value);
+                }
+                Task::Apply(op) => {
+                    let right = values.pop().unwrap();
+                    let left = values.pop().unwrap();
+                    values.push(op.apply(left, right));
+                }
+                Task::Fold(conjunction, count) => {
+                    let args = values.split_off(values.len() - count);
+                    let value = if conjunction {
+                        args.into_iter().all(|it| it)
+                    } else {
+                        args.into_iter().any(|it| it)
+                    };
+                    values.push(value);
+                }
+            }
+        }
+        return values.pop().unwrap();
+    }
+
+    /// Evaluate this expression in Kleene's strong three-valued logic, using `valuation`
+    /// to obtain the values of atoms (possibly `Unknown`).
+    ///
+    /// The result is sound: if it is `True` (`False`), the expression is true (false) for
+    /// every value of the unknown atoms. However, it is not complete, since the unknown
+    /// atoms are treated as independent of each other (e.g. `a & !a` is `Unknown` when `a`
+    /// is unknown). Use `eval_definite` to obtain an exact answer.
+    pub fn eval_kleene<F>(&self, valuation: F) -> Kleene
+    where
+        F: Fn(&A) -> Kleene,
+    {
+        return self.fold(|node, args: Vec<Kleene>| match node {
+            BoolExpr::Const(value) => Kleene::from(*value),
+            BoolExpr::Atom(atom) => valuation(atom),
+            BoolExpr::Not(_) => !args[0],
+            BoolExpr::Op { op, .. } => op.apply_kleene(args[0], args[1]),
+            BoolExpr::And(_) => args.into_iter().fold(Kleene::True, Kleene::and),
+            BoolExpr::Or(_) => args.into_iter().fold(Kleene::False, Kleene::or),
+        });
+    }
+}
+
+impl<A: Eq + Clone + Debug + Hash> BoolExpr<A> {
+    /// Evaluate this expression under a valuation where some atoms are `Unknown`, returning
+    /// `True` (`False`) if and only if the expression is true (false) for every value of
+    /// the unknown atoms.
+    ///
+    /// Unlike `eval_kleene`, the result is exact, but it may require a satisfiability check
+    /// of the expression restricted to the unknown atoms.
+    pub fn eval_definite<F>(&self, valuation: F) -> Kleene
+    where
+        F: Fn(&A) -> Kleene,
+    {
+        let approximation = self.eval_kleene(&valuation);
+        if approximation != Kleene::Unknown {
+            return approximation;
+        }
+        let restricted = self.replace_atoms(|atom| match valuation(atom).to_bool() {
+            Some(value) => BoolExpr::Const(value),
+            None => BoolExpr::Atom(atom.clone()),
+        });
+        return if !restricted.is_satisfiable() {
+            Kleene::False
+        } else if restricted.is_tautology() {
+            Kleene::True
+        } else {
+            Kleene::Unknown
+        };
+    }
+}
+
+impl BoolExpr<usize> {
+    /// Evaluate this expression with atoms interpreted as indices into the given `BitVector`.
+    pub fn eval_bit_vector<BV: BitVector>(&self, state: &BV) -> bool {
+        return self.eval(|index| state.get(*index));
+    }
+
+    /// Evaluate this expression with atoms interpreted as bit positions of the given `IdState`.
+    pub fn eval_id_state(&self, state: IdState) -> bool {
+        return self.eval(|index| state.get_bit(*index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::bitvectors::{ArrayBitVector, BitVector, BitVector58};
+    use crate::logic::{BoolExpr, BoolOp, Kleene};
+    use crate::IdState;
+
+    /// Three-valued valuation of atoms `0..n` given by a string of `0`, `1` and `?`.
+    fn kleene(values: &str) -> impl Fn(&usize) -> Kleene + '_ {
+        return move |atom| match values.as_bytes()[*atom] {
+            b'0' => Kleene::False,
+            b'1' => Kleene::True,
+            _ => Kleene::Unknown,
+        };
+    }
+
+    #[test]
+    fn eval_operators() {
+        let expected = vec![
+            (BoolOp::And, [false, false, false, true]),
+            (BoolOp::Or, [false, true, true, true]),
+            (BoolOp::Xor, [false, true, true, false]),
+            (BoolOp::Iff, [true, false, false, true]),
+            (BoolOp::Imp, [true, true, false, true]),
+        ];
+        for (op, table) in expected {
+            let expr = BoolExpr::Op {
+                op,
+                left: Box::new(BoolExpr::Atom(0)),
+                right: Box::new(BoolExpr::Atom(1)),
+            };
+            for (row, expected) in table.iter().enumerate() {
+                let state = IdState::from(row);
+                // Row index bits: atom 0 is the high bit, atom 1 is the low bit.
+                let valuation = |atom: &usize| state.get_bit(1 - *atom);
+                assert_eq!(*expected, expr.eval(valuation), "{:?} in row {}", op, row);
+            }
+        }
+    }
+
+    #[test]
+    fn eval_constants_and_nary() {
+        let atoms = vec![BoolExpr::Atom(0), BoolExpr::Atom(1), BoolExpr::Const(true)];
+        let and = BoolExpr::And(atoms.clone());
+        let or = BoolExpr::Or(atoms);
+        for state in 0..4 {
+            let state = IdState::from(state);
+            let (a, b) = (state.get_bit(0), state.get_bit(1));
+            assert_eq!(a && b, and.eval_id_state(state));
+            assert!(or.eval_id_state(state));
+        }
+        assert!(BoolExpr::<usize>::And(vec![]).eval_id_state(IdState::from(0)));
+        assert!(!BoolExpr::<usize>::Or(vec![]).eval_id_state(IdState::from(0)));
+        assert!(!BoolExpr::<usize>::Const(false).eval_id_state(IdState::from(0)));
+        let mixed = BoolExpr::Or(vec![
+            BoolExpr::Atom(1),
+            BoolExpr::Not(Box::new(BoolExpr::Atom(0))),
+        ]);
+        assert!(!mixed.eval_id_state(IdState::from(0b01)));
+        assert!(mixed.eval_id_state(IdState::from(0b11)));
+    }
+
+    #[test]
+    fn eval_states() {
+        let expr: BoolExpr<String> = "a & !(b | c) => d".parse().unwrap();
+        let names = ["a", "b", "c", "d"];
+        let expr = expr.map_atoms(|atom| names.iter().position(|n| n == atom).unwrap());
+        for state in 0..16 {
+            let state = IdState::from(state);
+            let bits: Vec<bool> = (0..4).map(|i| state.get_bit(i)).collect();
+            let expected = !(bits[0] && !(bits[1] || bits[2])) || bits[3];
+            assert_eq!(expected, expr.eval_id_state(state));
+            assert_eq!(
+                expected,
+                expr.eval_bit_vector(&BitVector58::from(bits.clone()))
+            );
+            assert_eq!(expected, expr.eval_bit_vector(&ArrayBitVector::from(bits)));
+        }
+        assert!(!expr.eval_bit_vector(&BitVector58::from_ones(4, vec![0])));
+    }
+
+    #[test]
+    fn eval_deep_expression() {
+        let mut expr = BoolExpr::Atom(0);
+        for i in 0..100_000 {
+            expr = BoolExpr::Op {
+                op: BoolOp::Xor,
+                left: Box::new(BoolExpr::Not(Box::new(expr))),
+                right: Box::new(BoolExpr::Atom(i % 2)),
+            };
+        }
+        assert!(expr.eval(|atom| *atom == 0));
+    }
+
+    #[test]
+    fn eval_kleene_operators() {
+        let values = [Kleene::False, Kleene::True, Kleene::Unknown];
+        let ops = [
+            BoolOp::And,
+            BoolOp::Or,
+            BoolOp::Xor,
+            BoolOp::Iff,
+            BoolOp::Imp,
+        ];
+        for op in ops {
+            for left in values {
+                for right in values {
+                    // The result is known if and only if all completions agree.
+                    let completions = |value: Kleene| match value.to_bool() {
+                        Some(value) => vec![value],
+                        None => vec![false, true],
+                    };
+                    let mut results = Vec::new();
+                    for l in completions(left) {
+                        for r in completions(right) {
+                            results.push(op.apply(l, r));
+                        }
+                    }
+                    let expected = if results.iter().all(|it| *it == results[0]) {
+                        Kleene::from(results[0])
+                    } else {
+                        Kleene::Unknown
+                    };
+                    assert_eq!(expected, op.apply_kleene(left, right));
+                }
+            }
+        }
+        assert_eq!(Kleene::Unknown, !Kleene::Unknown);
+        assert_eq!(Kleene::Unknown, Kleene::from(None));
+        assert_eq!(Some(false), Kleene::False.to_bool());
+    }
+
+    #[test]
+    fn eval_kleene_expressions() {
+        let expr: BoolExpr<String> = "a & (b | !c) | a & c".parse().unwrap();
+        let expr = expr.map_atoms(|atom| (atom.as_bytes()[0] - b'a') as usize);
+        assert_eq!(Kleene::False, expr.eval_kleene(kleene("0??")));
+        assert_eq!(Kleene::True, expr.eval_kleene(kleene("11?")));
+        assert_eq!(Kleene::True, expr.eval_kleene(kleene("1?1")));
+        assert_eq!(Kleene::Unknown, expr.eval_kleene(kleene("10?")));
+        assert_eq!(Kleene::Unknown, expr.eval_kleene(kleene("1??")));
+        // Both values of `b` and `c` make the expression true.
+        assert_eq!(Kleene::True, expr.eval_definite(kleene("1??")));
+        assert_eq!(Kleene::Unknown, expr.eval_definite(kleene("?11")));
+        let empty = BoolExpr::<usize>::And(vec![]);
+        assert_eq!(Kleene::True, empty.eval_kleene(kleene("")));
+        // `a ^ a` is always false, `a ^ b` depends on the unknown values.
+        let xor: BoolExpr<usize> = BoolExpr::Op {
+            op: BoolOp::Xor,
+            left: Box::new(BoolExpr::Atom(0)),
+            right: Box::new(BoolExpr::Atom(0)),
+        };
+        assert_eq!(Kleene::Unknown, xor.eval_kleene(kleene("?")));
+        assert_eq!(Kleene::False, xor.eval_definite(kleene("?")));
+    }
+
+    #[test]
+    fn eval_definite_exhaustive() {
+        // Compare with all completions of every three-valued valuation.
+        let expr: BoolExpr<String> = "(a => b) & (b ^ c) | !a & c".parse().unwrap();
+        let expr = expr.map_atoms(|atom| (atom.as_bytes()[0] - b'a') as usize);
+        for row in 0..27 {
+            let values: String = (0..3)
+                .map(|i| ['0', '1', '?'][(row / 3usize.pow(i)) % 3])
+                .collect();
+            let results: Vec<bool> = (0..8usize)
+                .map(IdState::from)
+                .filter(|state| {
+                    (0..3).all(|i| match values.as_bytes()[i] {
+                        b'0' => !state.get_bit(i),
+                        b'1' => state.get_bit(i),
+                        _ => true,
+                    })
+                })
+                .map(|state| expr.eval_id_state(state))
+                .collect();
+            let definite = expr.eval_definite(kleene(&values));
+            if results.iter().all(|it| *it) {
+                assert_eq!(Kleene::True, definite, "{}", values);
+            } else if results.iter().all(|it| !*it) {
+                assert_eq!(Kleene::False, definite, "{}", values);
+            } else {
+                assert_eq!(Kleene::Unknown, definite, "{}", values);
+                assert_eq!(Kleene::Unknown, expr.eval_kleene(kleene(&values)));
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use super::{BoolExpr, BoolOp};
+use std::fmt::Debug;
+
+impl<A: Eq + Clone + Debug> BoolExpr<A> {
+    /// Replace all chains of binary conjunctions and disjunctions with n-ary `And`/`Or` nodes.
+    ///
+    /// Nested n-ary nodes of the same kind are merged as well, so the arguments of a flattened
+    /// `And` are never conjunctions themselves (and the same holds for `Or`).
+    pub fn flatten(&self) -> BoolExpr<A> {
+        return self.fold(|node, children| match node {
+            BoolExpr::Op {
+                op: BoolOp::And, ..
+            }
+            | BoolExpr::And(_) => BoolExpr::And(merge_args(BoolOp::And, children)),
+            BoolExpr::Op { op: BoolOp::Or, .. } | BoolExpr::Or(_) => {
+                BoolExpr::Or(merge_args(BoolOp::Or, children))
+            }
+            BoolExpr::Atom(_) => node.clone(),
+            _ => node.with_children(children),
+        });
+    }
+
+    /// Replace all n-ary `And`/`Or` nodes with left-leaning chains of binary operators, i.e.
+    /// the shape produced by the parser. Empty nodes are replaced by the respective constants
+    /// and nodes with a single argument by the argument itself.
+    pub fn unflatten(&self) -> BoolExpr<A> {
+        return self.fold(|node, children| match node {
+            BoolExpr::And(_) => binary_chain(BoolOp::And, children),
+            BoolExpr::Or(_) => binary_chain(BoolOp::Or, children),
+            BoolExpr::Atom(_) => node.clone(),
+            _ => node.with_children(children),
+        });
+    }
+}
+
+/// **(internal)** Merge already flattened arguments of an `op` node: arguments which are
+/// n-ary nodes of the same kind are replaced by their own arguments.
+fn merge_args<A: Eq + Clone + Debug>(op: BoolOp, args: Vec<BoolExpr<A>>) -> Vec<BoolExpr<A>> {
+    let mut result = Vec::new();
+    for arg in args {
+        match arg {
+            BoolExpr::And(_) if op == BoolOp::And => result.extend(arg.into_children()),
+            BoolExpr::Or(_) if op == BoolOp::Or => result.extend(arg.into_children()),
+            _ => result.push(arg),
+        }
+    }
+    return result;
+}
+
+/// **(internal)** Build a left-leaning chain of binary `op` nodes over `args`.
+fn binary_chain<A: Eq + Clone + Debug>(op: BoolOp, args: Vec<BoolExpr<A>>) -> BoolExpr<A> {
+    let mut args = args.into_iter();
+    let mut result = match args.next() {
+        // `true` is neutral for conjunction, `false` for disjunction.
+        None => return BoolExpr::Const(op == BoolOp::And),
+        Some(first) => first,
+    };
+    for arg in args {
+        result = BoolExpr::Op {
+            op,
+            left: Box::new(result),
+            right: Box::new(arg),
+        };
+    }
+    return result;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::test_util::{atom, parse};
+    use crate::logic::BoolExpr;
+
+    #[test]
+    fn flatten_chains() {
+        let expr = parse("a & (b & c) & !(d | e | (f | g)) & (a => b)");
+        let expected = BoolExpr::And(vec![
+            atom("a"),
+            atom("b"),
+            atom("c"),
+            BoolExpr::Not(Box::new(BoolExpr::Or(vec![
+                atom("d"),
+                atom("e"),
+                atom("f"),
+                atom("g"),
+            ]))),
+            parse("a => b"),
+        ]);
+        assert_eq!(expected, expr.flatten());
+        let nested = BoolExpr::Or(vec![
+            BoolExpr::Or(vec![atom("a"), parse("b | c")]),
+            BoolExpr::And(vec![atom("d")]),
+        ]);
+        assert_eq!(
+            BoolExpr::Or(vec![
+                atom("a"),
+                atom("b"),
+                atom("c"),
+                BoolExpr::And(vec![atom("d")])
+            ]),
+            nested.flatten()
+        );
+    }
+
+    #[test]
+    fn unflatten_chains() {
+        for input in &["a & b & c & d", "a | b & c | (d ^ e)", "!(a | b | c) => d"] {
+            let expr = parse(input);
+            assert_eq!(expr, expr.flatten().unflatten());
+        }
+        assert_eq!(
+            BoolExpr::Const(true),
+            BoolExpr::<String>::And(vec![]).unflatten()
+        );
+        assert_eq!(
+            BoolExpr::Const(false),
+            BoolExpr::<String>::Or(vec![]).unflatten()
+        );
+        assert_eq!(atom("a"), BoolExpr::And(vec![atom("a")]).unflatten());
+    }
+
+    #[test]
+    fn flat_display_round_trip() {
+        let expr = parse("(a & b) & (c & (d | e | f)) | g").flatten();
+        let printed = expr.to_string();
+        assert_eq!("a & b & c & (d | e | f) | g", printed);
+        assert_eq!(expr, parse(&printed).flatten());
+    }
+}
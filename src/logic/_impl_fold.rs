@@ -0,0 +1,294 @@
+use super::{BoolExpr, BoolExprFold, BoolExprVisitor};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+impl<A: Eq + Clone + Debug> BoolExpr<A> {
+    /// Immediate subexpressions of this expression (from left to right).
+    pub fn children(&self) -> Vec<&BoolExpr<A>> {
+        return match self {
+            BoolExpr::Const(_) | BoolExpr::Atom(_) => Vec::new(),
+            BoolExpr::Not(inner) => vec![inner],
+            BoolExpr::Op { left, right, .. } => vec![left, right],
+            BoolExpr::And(args) | BoolExpr::Or(args) => args.iter().collect(),
+        };
+    }
+
+    /// Traverse this expression depth-first (from left to right) using the given `visitor`.
+    pub fn visit<V: BoolExprVisitor<A>>(&self, visitor: &mut V) {
+        // Each node is on the stack twice: first to enter it, then to exit it.
+        let mut stack = vec![(self, false)];
+        while let Some((node, exiting)) = stack.pop() {
+            if exiting {
+                visitor.exit(node);
+            } else {
+                stack.push((node, true));
+                if visitor.enter(node) {
+                    stack.extend(node.children().into_iter().rev().map(|c| (c, false)));
+                }
+            }
+        }
+    }
+
+    /// Compute the result of the given `folder` for this expression.
+    pub fn fold_with<F: BoolExprFold<A>>(&self, folder: &mut F) -> F::Output {
+        // Each node is on the stack twice: first to schedule its children, then (once
+        // the children are done) to compute its own value.
+        let mut stack = vec![(self, false)];
+        let mut results: Vec<F::Output> = Vec::new();
+        while let Some((node, expanded)) = stack.pop() {
+            if expanded {
+                let count = node.children_count();
+                let args = results.split_off(results.len() - count);
+                results.push(folder.exit(node, args));
+            } else if let Some(result) = folder.enter(node) {
+                results.push(result);
+            } else {
+                stack.push((node, true));
+                stack.extend(node.children().into_iter().rev().map(|c| (c, false)));
+            }
+        }
+        return results.pop().unwrap();
+    }
+
+    /// Compute a value for this expression bottom-up: `f` is called for every subexpression
+    /// together with the already computed values of its children (in the same order as
+    /// returned by `children`).
+    ///
+    /// This is a shorthand for `fold_with` where only the `exit` hook is needed.
+    pub fn fold<R, F>(&self, f: F) -> R
+    where
+        F: FnMut(&BoolExpr<A>, Vec<R>) -> R,
+    {
+        return self.fold_with(&mut ClosureFold(f, PhantomData));
+    }
+
+    /// **(internal)** Number of immediate subexpressions.
+    fn children_count(&self) -> usize {
+        return match self {
+            BoolExpr::Const(_) | BoolExpr::Atom(_) => 0,
+            BoolExpr::Not(_) => 1,
+            BoolExpr::Op { .. } => 2,
+            BoolExpr::And(args) | BoolExpr::Or(args) => args.len(),
+        };
+    }
+
+    /// **(internal)** Create a node of the same kind as this one (including operator), but
+    /// with the given `children`. Must not be called on atoms.
+    pub(super) fn with_children<B: Eq + Clone + Debug>(
+        &self,
+        mut children: Vec<BoolExpr<B>>,
+    ) -> BoolExpr<B> {
+        return match self {
+            BoolExpr::Const(value) => BoolExpr::Const(*value),
+            BoolExpr::Atom(_) => panic!("Atoms have no children."),
+            BoolExpr::Not(_) => BoolExpr::Not(Box::new(children.pop().unwrap())),
+            BoolExpr::Op { op, .. } => {
+                let right = children.pop().unwrap();
+                let left = children.pop().unwrap();
+                BoolExpr::Op {
+                    op: *op,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                }
+            }
+            BoolExpr::And(_) => BoolExpr::And(children),
+            BoolExpr::Or(_) => BoolExpr::Or(children),
+        };
+    }
+
+    /// **(internal)** Consume this node, returning its children.
+    ///
+    /// Since `BoolExpr` implements `Drop`, its fields cannot be moved out using a pattern,
+    /// so this is the way to take ownership of the children.
+    pub(super) fn into_children(mut self) -> Vec<BoolExpr<A>> {
+        let mut children = Vec::new();
+        self.take_children(&mut children);
+        return children;
+    }
+
+    /// **(internal)** Move all children of this node into `stack`, leaving the node itself
+    /// with trivial (placeholder) children.
+    fn take_children(&mut self, stack: &mut Vec<BoolExpr<A>>) {
+        let placeholder = || BoolExpr::Const(false);
+        match self {
+            BoolExpr::Const(_) | BoolExpr::Atom(_) => {}
+            BoolExpr::Not(inner) => stack.push(std::mem::replace(inner, placeholder())),
+            BoolExpr::Op { left, right, .. } => {
+                stack.push(std::mem::replace(left, placeholder()));
+                stack.push(std::mem::replace(right, placeholder()));
+            }
+            BoolExpr::And(args) | BoolExpr::Or(args) => stack.append(args),
+        }
+    }
+}
+
+impl<A: Eq + Clone + Debug> Drop for BoolExpr<A> {
+    fn drop(&mut self) {
+        // Children are moved to an explicit stack, so that each node is dropped only once
+        // it has no non-trivial children (and the default drop glue does not recurse).
+        let mut stack = Vec::new();
+        self.take_children(&mut stack);
+        while let Some(mut expr) = stack.pop() {
+            expr.take_children(&mut stack);
+        }
+    }
+}
+
+/// **(internal)** Adapter which turns a closure into a `BoolExprFold`.
+struct ClosureFold<F, R>(F, PhantomData<R>);
+
+impl<A, R, F> BoolExprFold<A> for ClosureFold<F, R>
+where
+    A: Eq + Clone + Debug,
+    F: FnMut(&BoolExpr<A>, Vec<R>) -> R,
+{
+    type Output = R;
+
+    fn exit(&mut self, expr: &BoolExpr<A>, children: Vec<R>) -> R {
+        return (self.0)(expr, children);
+    }
+}
+
+impl<A: Eq + Clone + Debug> Clone for BoolExpr<A> {
+    fn clone(&self) -> Self {
+        return self.fold(|node, children| match node {
+            BoolExpr::Atom(atom) => BoolExpr::Atom(atom.clone()),
+            _ => node.with_children(children),
+        });
+    }
+}
+
+impl<A: Eq + Clone + Debug> PartialEq for BoolExpr<A> {
+    fn eq(&self, other: &Self) -> bool {
+        let mut stack = vec![(self, other)];
+        while let Some((left, right)) = stack.pop() {
+            let same_node = match (left, right) {
+                (BoolExpr::Const(a), BoolExpr::Const(b)) => a == b,
+                (BoolExpr::Atom(a), BoolExpr::Atom(b)) => a == b,
+                (BoolExpr::Not(_), BoolExpr::Not(_)) => true,
+                (BoolExpr::Op { op: a, .. }, BoolExpr::Op { op: b, .. }) => a == b,
+                (BoolExpr::And(a), BoolExpr::And(b)) | (BoolExpr::Or(a), BoolExpr::Or(b)) => {
+                    a.len() == b.len()
+                }
+                _ => false,
+            };
+            if !same_node {
+                return false;
+            }
+            stack.extend(left.children().into_iter().zip(right.children()));
+        }
+        return true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::{BoolExpr, BoolExprFold, BoolExprVisitor, BoolOp};
+
+    /// Build an expression which is nested `depth` levels deep.
+    fn deep_expression(depth: usize) -> BoolExpr<usize> {
+        let mut expr = BoolExpr::Atom(0);
+        for i in 0..depth {
+            expr = match i % 3 {
+                0 => BoolExpr::Not(Box::new(expr)),
+                1 => BoolExpr::And(vec![BoolExpr::Atom(i), expr]),
+                _ => BoolExpr::Op {
+                    op: BoolOp::Or,
+                    left: Box::new(expr),
+                    right: Box::new(BoolExpr::Atom(i)),
+                },
+            };
+        }
+        return expr;
+    }
+
+    /// Collects atoms, but does not descend into negations.
+    struct PositiveAtoms(Vec<String>, usize);
+
+    impl BoolExprVisitor<String> for PositiveAtoms {
+        fn enter(&mut self, expr: &BoolExpr<String>) -> bool {
+            if let BoolExpr::Atom(name) = expr {
+                self.0.push(name.clone());
+            }
+            return !matches!(expr, BoolExpr::Not(_));
+        }
+
+        fn exit(&mut self, _expr: &BoolExpr<String>) {
+            self.1 += 1;
+        }
+    }
+
+    /// Computes expression size, but counts each negated subexpression as one node.
+    struct NegationAsLeaf;
+
+    impl BoolExprFold<String> for NegationAsLeaf {
+        type Output = usize;
+
+        fn enter(&mut self, expr: &BoolExpr<String>) -> Option<usize> {
+            return if let BoolExpr::Not(_) = expr {
+                Some(1)
+            } else {
+                None
+            };
+        }
+
+        fn exit(&mut self, _expr: &BoolExpr<String>, children: Vec<usize>) -> usize {
+            return 1 + children.into_iter().sum::<usize>();
+        }
+    }
+
+    #[test]
+    fn fold_children_in_order() {
+        let expr: BoolExpr<String> = "a & !(b | true) => c ^ d".parse().unwrap();
+        let printed = expr.fold(|node, args: Vec<String>| match node {
+            BoolExpr::Atom(name) => name.clone(),
+            BoolExpr::Const(value) => value.to_string(),
+            _ => format!("{}[{}]", node.children().len(), args.join(",")),
+        });
+        assert_eq!("2[2[a,1[2[b,true]]],2[c,d]]", printed);
+        let size = expr.fold(|_, args: Vec<usize>| 1 + args.iter().sum::<usize>());
+        assert_eq!(10, size);
+        assert_eq!(7, expr.fold_with(&mut NegationAsLeaf));
+    }
+
+    #[test]
+    fn visit_with_pruning() {
+        let expr: BoolExpr<String> = "a & !(b | c) | d & !!e".parse().unwrap();
+        let mut visitor = PositiveAtoms(Vec::new(), 0);
+        expr.visit(&mut visitor);
+        assert_eq!(vec!["a", "d"], visitor.0);
+        assert_eq!(7, visitor.1);
+    }
+
+    #[test]
+    fn deep_expressions_are_stack_safe() {
+        let depth = 100_000;
+        let expr = deep_expression(depth);
+        let copy = expr.clone();
+        let shallower = deep_expression(depth - 1);
+        assert_eq!(expr, copy);
+        assert_ne!(expr, shallower);
+        let height = expr.fold(|_, c: Vec<usize>| 1 + c.into_iter().max().unwrap_or(0));
+        assert_eq!(depth + 1, height);
+        let atoms = (0..depth).filter(|i| i % 3 != 0).count() + 1;
+        assert_eq!(atoms, expr.atoms().len());
+        let renamed = expr.map_atoms(|atom| atom + 1);
+        let value = expr.eval(|_| true);
+        assert_eq!(value, renamed.eval(|_| true));
+        // Operations implemented using folds are stack-safe as well.
+        let simplified = expr.simplify();
+        assert_eq!(value, simplified.eval(|_| true));
+        let flat = expr.flatten();
+        let unflattened = flat.unflatten();
+        let expected = copy.unflatten();
+        assert_eq!(expected, unflattened);
+        let mut fresh = depth;
+        let cnf = expr.to_cnf_tseitin(|| {
+            fresh += 1;
+            fresh
+        });
+        assert!(cnf.is_cnf());
+        assert!(expr.to_string().len() > depth);
+        // All of the deep expressions are also dropped without recursion at the end.
+    }
+}
@@ -0,0 +1,231 @@
+use super::{BoolExpr, FormulaArena, FormulaId, FormulaNode};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+impl<A: Eq + Clone + Debug + Hash> FormulaNode<A> {
+    /// Identifiers of the immediate subformulas of this node (from left to right).
+    pub fn children(&self) -> Vec<FormulaId> {
+        return match self {
+            FormulaNode::Const(_) | FormulaNode::Atom(_) => Vec::new(),
+            FormulaNode::Not(inner) => vec![*inner],
+            FormulaNode::Op { left, right, .. } => vec![*left, *right],
+            FormulaNode::And(args) | FormulaNode::Or(args) => args.clone(),
+        };
+    }
+}
+
+impl<A: Eq + Clone + Debug + Hash> Default for FormulaArena<A> {
+    fn default() -> Self {
+        return FormulaArena::new();
+    }
+}
+
+impl<A: Eq + Clone + Debug + Hash> FormulaArena<A> {
+    /// Create a new empty arena.
+    pub fn new() -> FormulaArena<A> {
+        return FormulaArena {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+        };
+    }
+
+    /// Number of distinct formulas stored in this arena.
+    pub fn len(&self) -> usize {
+        return self.nodes.len();
+    }
+
+    /// True if there are no formulas in this arena.
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.is_empty();
+    }
+
+    /// Obtain the node of the formula with the given id.
+    ///
+    /// Panics if the id does not belong to this arena.
+    pub fn node(&self, id: FormulaId) -> &FormulaNode<A> {
+        return &self.nodes[id.0];
+    }
+
+    /// Obtain an id for the given node, adding the node to the arena if it is not present yet.
+    ///
+    /// Children of the node must already be present in this arena.
+    pub fn intern(&mut self, node: FormulaNode<A>) -> FormulaId {
+        if cfg!(feature = "shields_up") {
+            if let Some(child) = node.children().iter().find(|c| c.0 >= self.nodes.len()) {
+                panic!("Child {:?} is not present in the arena.", child);
+            }
+        }
+        if let Some(id) = self.index.get(&node) {
+            return *id;
+        }
+        let id = FormulaId(self.nodes.len());
+        self.nodes.push(node.clone());
+        self.index.insert(node, id);
+        return id;
+    }
+
+    /// Add all subformulas of the given expression to this arena and return the id of `expr`.
+    pub fn add(&mut self, expr: &BoolExpr<A>) -> FormulaId {
+        return expr.fold(|node, mut children| {
+            let node = match node {
+                BoolExpr::Const(value) => FormulaNode::Const(*value),
+                BoolExpr::Atom(atom) => FormulaNode::Atom(atom.clone()),
+                BoolExpr::Not(_) => FormulaNode::Not(children[0]),
+                BoolExpr::Op { op, .. } => {
+                    let right = children.pop().unwrap();
+                    let left = children.pop().unwrap();
+                    FormulaNode::Op {
+                        op: *op,
+                        left,
+                        right,
+                    }
+                }
+                BoolExpr::And(_) => FormulaNode::And(children),
+                BoolExpr::Or(_) => FormulaNode::Or(children),
+            };
+            self.intern(node)
+        });
+    }
+
+    /// Reconstruct the tree representation of the formula with the given id.
+    ///
+    /// Note that shared subformulas are duplicated in the result, so it can be exponentially
+    /// larger than the number of nodes in the arena.
+    pub fn to_expr(&self, id: FormulaId) -> BoolExpr<A> {
+        let mut stack = vec![(id, false)];
+        let mut results: Vec<BoolExpr<A>> = Vec::new();
+        while let Some((id, expanded)) = stack.pop() {
+            let node = self.node(id);
+            if !expanded {
+                stack.push((id, true));
+                stack.extend(node.children().into_iter().rev().map(|c| (c, false)));
+                continue;
+            }
+            let expr = match node {
+                FormulaNode::Const(value) => BoolExpr::Const(*value),
+                FormulaNode::Atom(atom) => BoolExpr::Atom(atom.clone()),
+                FormulaNode::Not(_) => BoolExpr::Not(Box::new(results.pop().unwrap())),
+                FormulaNode::Op { op, .. } => {
+                    let right = results.pop().unwrap();
+                    let left = results.pop().unwrap();
+                    BoolExpr::Op {
+                        op: *op,
+                        left: Box::new(left),
+                        right: Box::new(right),
+                    }
+                }
+                FormulaNode::And(args) => {
+                    BoolExpr::And(results.split_off(results.len() - args.len()))
+                }
+                FormulaNode::Or(args) => {
+                    BoolExpr::Or(results.split_off(results.len() - args.len()))
+                }
+            };
+            results.push(expr);
+        }
+        return results.pop().unwrap();
+    }
+
+    /// Compute a value for the formula with the given id bottom-up, similar to `BoolExpr::fold`.
+    /// However, `f` is called only once for every distinct subformula and the result is then
+    /// reused for all its occurrences.
+    ///
+    /// The traversal uses an explicit stack, so it works for arbitrarily deep formulas.
+    pub fn fold<R, F>(&self, id: FormulaId, mut f: F) -> R
+    where
+        R: Clone,
+        F: FnMut(FormulaId, &FormulaNode<A>, Vec<R>) -> R,
+    {
+        let mut cache: HashMap<FormulaId, R> = HashMap::new();
+        let mut stack = vec![(id, false)];
+        while let Some((id, expanded)) = stack.pop() {
+            if cache.contains_key(&id) {
+                continue;
+            }
+            let node = self.node(id);
+            let children = node.children();
+            if expanded {
+                let args = children.iter().map(|c| cache[c].clone()).collect();
+                let result = f(id, node, args);
+                cache.insert(id, result);
+            } else {
+                stack.push((id, true));
+                stack.extend(children.into_iter().rev().map(|c| (c, false)));
+            }
+        }
+        return cache.remove(&id).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::test_util::parse;
+    use crate::logic::{BoolExpr, BoolOp, FormulaArena, FormulaNode};
+
+    #[test]
+    fn arena_shares_subformulas() {
+        let mut arena = FormulaArena::new();
+        assert!(arena.is_empty());
+        let first = arena.add(&parse("(a | b) & !(a | b) => c"));
+        assert_eq!(7, arena.len());
+        let second = arena.add(&parse("!(a | b) ^ (c => c)"));
+        assert_eq!(9, arena.len());
+        assert_ne!(first, second);
+        assert_eq!(first, arena.add(&parse("(a | b) & !(a | b) => c")));
+        let a_or_b = arena.add(&parse("a | b"));
+        let a = arena.intern(FormulaNode::Atom("a".to_string()));
+        let b = arena.intern(FormulaNode::Atom("b".to_string()));
+        assert_eq!(
+            &FormulaNode::Op {
+                op: BoolOp::Or,
+                left: a,
+                right: b
+            },
+            arena.node(a_or_b)
+        );
+        assert_eq!(9, arena.len());
+        // Different shapes are different formulas.
+        let flat = arena.add(&BoolExpr::Or(vec![parse("a"), parse("b")]));
+        assert_eq!(&FormulaNode::Or(vec![a, b]), arena.node(flat));
+        assert_ne!(flat, a_or_b);
+    }
+
+    #[test]
+    fn arena_round_trip() {
+        let mut arena = FormulaArena::new();
+        for input in &["a", "true & !false", "a & (b | c) => !(d <=> e) ^ a"] {
+            let expr = parse(input);
+            let id = arena.add(&expr);
+            assert_eq!(expr, arena.to_expr(id));
+        }
+        let nary = BoolExpr::And(vec![parse("a"), BoolExpr::Or(vec![]), parse("!b")]);
+        let id = arena.add(&nary);
+        assert_eq!(nary, arena.to_expr(id));
+    }
+
+    #[test]
+    fn arena_memoized_fold() {
+        // A formula of 2^20 leaves which has only 21 distinct subformulas.
+        let mut arena = FormulaArena::new();
+        let mut id = arena.intern(FormulaNode::Atom(0));
+        for _ in 0..20 {
+            id = arena.intern(FormulaNode::Op {
+                op: BoolOp::Xor,
+                left: id,
+                right: id,
+            });
+        }
+        assert_eq!(21, arena.len());
+        let mut calls = 0;
+        let leaves = arena.fold(id, |_, node, children: Vec<u64>| {
+            calls += 1;
+            match node {
+                FormulaNode::Atom(_) => 1,
+                _ => children.into_iter().sum(),
+            }
+        });
+        assert_eq!(1 << 20, leaves);
+        assert_eq!(21, calls);
+    }
+}
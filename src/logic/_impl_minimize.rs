@@ -0,0 +1,422 @@
+use super::BoolExpr;
+use crate::collections::bitvectors::BitVector;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// **(internal)** Expressions with at most this many atoms are minimized using their truth
+/// table (see `COVER_BUDGET`); larger expressions are minimized heuristically.
+const EXACT_LIMIT: usize = 10;
+
+/// **(internal)** Maximal number of nodes explored when searching for a minimal cover by
+/// prime implicants.
+const COVER_BUDGET: usize = 20_000;
+
+/// **(internal)** A cube over at most `usize::BITS` atoms: atoms with a set bit in `mask`
+/// are don't-cares, the values of other atoms are given by `bits`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct Implicant {
+    bits: usize,
+    mask: usize,
+}
+
+impl Implicant {
+    fn covers(&self, row: usize) -> bool {
+        return row & !self.mask == self.bits;
+    }
+
+    fn literals(&self, atom_count: usize) -> usize {
+        return atom_count - self.mask.count_ones() as usize;
+    }
+
+    fn to_cube(self, atom_count: usize) -> Vec<Option<bool>> {
+        return (0..atom_count)
+            .map(|i| {
+                if (self.mask >> i) & 1 == 1 {
+                    None
+                } else {
+                    Some((self.bits >> i) & 1 == 1)
+                }
+            })
+            .collect();
+    }
+}
+
+impl<A: Eq + Clone + Debug + Hash> BoolExpr<A> {
+    /// Compute a small equivalent expression in disjunctive normal form (an `Or` of `And`
+    /// cubes of literals), e.g. for presenting a function in a readable way.
+    ///
+    /// Expressions with a small support are minimized using their truth table (usually
+    /// exactly, see `minimize_truth_table`). For larger expressions, every cube is expanded
+    /// into a prime implicant and redundant cubes are removed, so the result is irredundant,
+    /// but not necessarily minimal.
+    pub fn minimize(&self) -> BoolExpr<A> {
+        let atoms = self.atoms();
+        if atoms.len() <= EXACT_LIMIT {
+            return BoolExpr::minimize_truth_table(&self.truth_table(&atoms), &atoms);
+        }
+        // Solver for the negation: a cube is an implicant if it contradicts the negation.
+        let mut negation = super::SatSolver::new();
+        negation.add_expr(&BoolExpr::Not(Box::new(self.clone())));
+        let mut cubes: Vec<Vec<Option<bool>>> = Vec::new();
+        let mut seen = HashSet::new();
+        for mut cube in self.satisfying_cubes(&atoms) {
+            // Expand the cube by dropping every literal which is not needed.
+            for i in 0..cube.len() {
+                if cube[i].is_none() {
+                    continue;
+                }
+                let removed = cube[i].take();
+                let assumptions = cube_literals(&cube, &atoms);
+                if negation.solve_with_assumptions(&assumptions).is_some() {
+                    cube[i] = removed;
+                }
+            }
+            if seen.insert(cube.clone()) {
+                cubes.push(cube);
+            }
+        }
+        // Remove cubes covered by the remaining ones, trying the largest cubes (with the
+        // most literals) first.
+        cubes.sort_by_key(|cube| std::cmp::Reverse(cube.iter().filter(|v| v.is_some()).count()));
+        let mut k = 0;
+        while k < cubes.len() {
+            let others = cubes
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != k)
+                .map(|(_, cube)| cube_expr(cube, &atoms));
+            let query = BoolExpr::And(vec![
+                cube_expr(&cubes[k], &atoms),
+                BoolExpr::Not(Box::new(BoolExpr::Or(others.collect()))),
+            ]);
+            if query.is_satisfiable() {
+                k += 1;
+            } else {
+                cubes.remove(k);
+            }
+        }
+        return dnf_expr(cubes, &atoms);
+    }
+
+    /// Compute a minimal DNF of the function given by a truth table over the given `atoms`
+    /// (see `truth_table` for the layout of the table) using the Quine–McCluskey method.
+    ///
+    /// All cubes are prime implicants. The search for the cover with the smallest number of
+    /// cubes (and among such covers, the smallest number of literals) explores a bounded
+    /// number of candidates, so the result is minimal for functions with a moderate number
+    /// of prime implicants. For other functions (e.g. random functions of ten atoms), it
+    /// is the best cover found within the budget (but at most as large as a greedy cover).
+    ///
+    /// Panics if the length of the table is not $2^n$ for $n$ atoms.
+    pub fn minimize_truth_table<BV: BitVector>(table: &BV, atoms: &[A]) -> BoolExpr<A> {
+        if atoms.len() >= std::mem::size_of::<usize>() * 8 || table.len() != 1 << atoms.len() {
+            panic!(
+                "Truth table of length {} does not match {} atoms.",
+                table.len(),
+                atoms.len()
+            );
+        }
+        let minterms = table.ones();
+        let primes = prime_implicants(&minterms, atoms.len());
+        let cover = minimal_cover(&minterms, &primes, atoms.len());
+        let cubes = cover
+            .into_iter()
+            .map(|i| primes[i].to_cube(atoms.len()))
+            .collect();
+        return dnf_expr(cubes, atoms);
+    }
+}
+
+/// **(internal)** Compute all prime implicants of the function with the given `minterms`
+/// by repeatedly merging pairs of implicants which differ in exactly one atom.
+fn prime_implicants(minterms: &[usize], atom_count: usize) -> Vec<Implicant> {
+    let mut current: HashSet<Implicant> = minterms
+        .iter()
+        .map(|row| Implicant {
+            bits: *row,
+            mask: 0,
+        })
+        .collect();
+    let mut primes = Vec::new();
+    while !current.is_empty() {
+        let mut next = HashSet::new();
+        let mut merged = HashSet::new();
+        for implicant in &current {
+            for i in 0..atom_count {
+                let bit = 1 << i;
+                if implicant.mask & bit != 0 || implicant.bits & bit != 0 {
+                    continue;
+                }
+                let partner = Implicant {
+                    bits: implicant.bits | bit,
+                    mask: implicant.mask,
+                };
+                if current.contains(&partner) {
+                    merged.insert(*implicant);
+                    merged.insert(partner);
+                    next.insert(Implicant {
+                        bits: implicant.bits,
+                        mask: implicant.mask | bit,
+                    });
+                }
+            }
+        }
+        primes.extend(current.into_iter().filter(|it| !merged.contains(it)));
+        current = next;
+    }
+    // Sets are iterated in an arbitrary order, but the (budgeted) cover search depends on
+    // the order of primes, so sort them to keep the results deterministic.
+    primes.sort_by_key(|it| (it.mask, it.bits));
+    return primes;
+}
+
+/// **(internal)** A node of the branch and bound search of `minimal_cover`.
+struct CoverNode {
+    selection: Vec<usize>,
+    /// Minterms covered by the selected primes.
+    covered: Vec<bool>,
+    /// Primes which must not be selected in this branch (they were already tried by an
+    /// earlier sibling branch, so selecting them again would only repeat the search).
+    excluded: Vec<bool>,
+}
+
+/// **(internal)** Select a minimal set of implicants (by the number of implicants, then by
+/// the number of literals) covering all `minterms`. Returns indices into `primes`.
+///
+/// The search starts from a greedy cover and explores at most `COVER_BUDGET` nodes; if the
+/// budget runs out, the best cover found so far is returned (it is not necessarily minimal).
+fn minimal_cover(minterms: &[usize], primes: &[Implicant], atom_count: usize) -> Vec<usize> {
+    // For every minterm, the primes which cover it, and for every prime, its minterms.
+    let covering: Vec<Vec<usize>> = minterms
+        .iter()
+        .map(|row| {
+            (0..primes.len())
+                .filter(|p| primes[*p].covers(*row))
+                .collect()
+        })
+        .collect();
+    let mut covered_by: Vec<Vec<usize>> = vec![Vec::new(); primes.len()];
+    for (m, options) in covering.iter().enumerate() {
+        for p in options {
+            covered_by[*p].push(m);
+        }
+    }
+    let literals = |p: usize| primes[p].literals(atom_count);
+    let cost = |selection: &[usize]| {
+        let total: usize = selection.iter().map(|p| literals(*p)).sum();
+        (selection.len(), total)
+    };
+    let mut best = greedy_cover(&covering, &covered_by, literals);
+    let mut best_cost = cost(&best);
+    let mut stack = vec![CoverNode {
+        selection: Vec::new(),
+        covered: vec![false; minterms.len()],
+        excluded: vec![false; primes.len()],
+    }];
+    let mut budget = COVER_BUDGET;
+    while let Some(node) = stack.pop() {
+        if budget == 0 {
+            break;
+        }
+        budget -= 1;
+        let options = |m: usize| {
+            let excluded = &node.excluded;
+            covering[m].iter().cloned().filter(move |p| !excluded[*p])
+        };
+        let mut uncovered: Vec<(usize, usize)> = (0..minterms.len())
+            .filter(|m| !node.covered[*m])
+            .map(|m| (options(m).count(), m))
+            .collect();
+        if uncovered.is_empty() {
+            if cost(&node.selection) < best_cost {
+                best_cost = cost(&node.selection);
+                best = node.selection;
+            }
+            continue;
+        }
+        uncovered.sort_unstable();
+        if uncovered[0].0 == 0 {
+            // Some minterm can no longer be covered in this branch.
+            continue;
+        }
+        // Lower bound: minterms which share no available prime need distinct primes.
+        let (mut bound, mut bound_literals) = cost(&node.selection);
+        let mut blocked = vec![false; minterms.len()];
+        for (_, m) in &uncovered {
+            if blocked[*m] {
+                continue;
+            }
+            bound += 1;
+            bound_literals += options(*m).map(literals).min().unwrap();
+            for p in options(*m) {
+                for other in &covered_by[p] {
+                    blocked[*other] = true;
+                }
+            }
+        }
+        if (bound, bound_literals) >= best_cost {
+            continue;
+        }
+        // Branch on the minterm with the fewest options (essential primes are thus selected
+        // without branching). The first option is explored first.
+        let branching: Vec<usize> = options(uncovered[0].1).collect();
+        for (i, p) in branching.iter().enumerate().rev() {
+            let mut child = CoverNode {
+                selection: node.selection.clone(),
+                covered: node.covered.clone(),
+                excluded: node.excluded.clone(),
+            };
+            child.selection.push(*p);
+            for m in &covered_by[*p] {
+                child.covered[*m] = true;
+            }
+            for q in &branching[..i] {
+                child.excluded[*q] = true;
+            }
+            stack.push(child);
+        }
+    }
+    return best;
+}
+
+/// **(internal)** A cover built by repeatedly selecting the prime which covers the most
+/// uncovered minterms (preferring primes with fewer literals).
+fn greedy_cover<F: Fn(usize) -> usize>(
+    covering: &[Vec<usize>],
+    covered_by: &[Vec<usize>],
+    literals: F,
+) -> Vec<usize> {
+    let mut covered = vec![false; covering.len()];
+    let mut selection = Vec::new();
+    while let Some(m) = covered.iter().position(|it| !*it) {
+        let gain = |p: usize| covered_by[p].iter().filter(|m| !covered[**m]).count();
+        let best = covering[m]
+            .iter()
+            .cloned()
+            .max_by_key(|p| (gain(*p), std::cmp::Reverse(literals(*p))))
+            .unwrap();
+        for m in &covered_by[best] {
+            covered[*m] = true;
+        }
+        selection.push(best);
+    }
+    return selection;
+}
+
+/// **(internal)** The literals of a cube as (atom, value) pairs.
+fn cube_literals<A: Clone>(cube: &[Option<bool>], atoms: &[A]) -> Vec<(A, bool)> {
+    return cube
+        .iter()
+        .zip(atoms)
+        .filter_map(|(value, atom)| value.map(|value| (atom.clone(), value)))
+        .collect();
+}
+
+/// **(internal)** A conjunction of the literals of a cube.
+fn cube_expr<A: Eq + Clone + Debug>(cube: &[Option<bool>], atoms: &[A]) -> BoolExpr<A> {
+    let literals = cube_literals(cube, atoms).into_iter().map(|(atom, value)| {
+        let atom = BoolExpr::Atom(atom);
+        if value {
+            atom
+        } else {
+            BoolExpr::Not(Box::new(atom))
+        }
+    });
+    return BoolExpr::And(literals.collect());
+}
+
+/// **(internal)** A disjunction of the given cubes. Cubes are sorted so that the result is
+/// deterministic: by the first atom, with positive literals first.
+fn dnf_expr<A: Eq + Clone + Debug>(mut cubes: Vec<Vec<Option<bool>>>, atoms: &[A]) -> BoolExpr<A> {
+    let rank = |value: &Option<bool>| match value {
+        Some(true) => 0,
+        Some(false) => 1,
+        None => 2,
+    };
+    cubes.sort_by_key(|cube| cube.iter().map(rank).collect::<Vec<_>>());
+    return BoolExpr::Or(cubes.iter().map(|cube| cube_expr(cube, atoms)).collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::bitvectors::{ArrayBitVector, BitVector};
+    use crate::logic::test_util::parse;
+    use crate::logic::BoolExpr;
+    use crate::testing::Random;
+    use std::time::{Duration, Instant};
+
+    /// Number of cubes and literals of a DNF.
+    fn size(dnf: &BoolExpr<String>) -> (usize, usize) {
+        let cubes = dnf.children();
+        let literals = cubes.iter().map(|cube| cube.children().len()).sum();
+        return (cubes.len(), literals);
+    }
+
+    #[test]
+    fn minimize_small() {
+        let cases = vec![
+            ("a & b | a & !b", "a"),
+            ("a | !a & b", "a | b"),
+            ("(a => b) & (b => a)", "a & b | !a & !b"),
+            ("a & !a", "false"),
+            ("a | !a", "true"),
+            ("!(a & b & c)", "!a | !b | !c"),
+            ("a & b | !a & c | b & c", "a & b | !a & c"),
+        ];
+        for (input, expected) in cases {
+            let minimized = parse(input).minimize();
+            assert!(minimized.is_dnf());
+            assert_eq!(expected, minimized.to_string(), "Minimizing {}", input);
+        }
+    }
+
+    #[test]
+    fn minimize_cyclic_cover() {
+        // Every minterm is covered by exactly two primes, so there are no essential primes,
+        // but three of the six primes are enough.
+        let atoms: Vec<String> = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let table = ArrayBitVector::from_ones(8, vec![0, 1, 2, 5, 6, 7]);
+        let minimized = BoolExpr::minimize_truth_table(&table, &atoms);
+        assert_eq!((3, 6), size(&minimized));
+        assert_eq!(table, minimized.truth_table(&atoms));
+    }
+
+    #[test]
+    fn minimize_large_support() {
+        // Too many atoms for the exact method.
+        let names: Vec<String> = (0..12).map(|i| format!("x{}", i)).collect();
+        let chain: Vec<String> = (0..6)
+            .map(|i| {
+                format!(
+                    "{} & {} | {} & !{}",
+                    names[2 * i],
+                    names[2 * i + 1],
+                    names[2 * i],
+                    names[2 * i + 1]
+                )
+            })
+            .collect();
+        let expr = parse(&format!("({}) & !x11", chain.join(" | ")));
+        let minimized = expr.minimize();
+        assert!(minimized.is_dnf());
+        assert!(minimized.is_equivalent(&expr));
+        assert_eq!((6, 12), size(&minimized));
+    }
+
+    #[test]
+    fn minimize_random_within_budget() {
+        // Random functions have many primes and no short covers, so the exact search must
+        // be bounded to finish in reasonable time.
+        let atoms: Vec<String> = (0..10).map(|i| format!("x{}", i)).collect();
+        let mut random = Random::new(16);
+        let start = Instant::now();
+        for _ in 0..2 {
+            let table: ArrayBitVector = random.bit_vector(1 << atoms.len());
+            let minimized = BoolExpr::minimize_truth_table(&table, &atoms);
+            assert!(minimized.is_dnf());
+            assert_eq!(table, minimized.truth_table(&atoms));
+        }
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,200 @@
+use super::ModelCount;
+use std::cmp::Ordering;
+use std::fmt::{Display, Error, Formatter};
+use std::ops::{Add, AddAssign, Shl, Shr};
+
+impl ModelCount {
+    /// The number $2^{exponent}$.
+    pub fn power_of_two(exponent: usize) -> ModelCount {
+        let mut digits = vec![0; exponent / 32];
+        digits.push(1 << (exponent % 32));
+        return ModelCount { digits };
+    }
+
+    pub fn is_zero(&self) -> bool {
+        return self.digits.is_empty();
+    }
+
+    /// The value of this count, if it fits into `u64`.
+    pub fn to_u64(&self) -> Option<u64> {
+        if self.digits.len() > 2 {
+            return None;
+        }
+        let mut result = 0;
+        for digit in self.digits.iter().rev() {
+            result = (result << 32) | u64::from(*digit);
+        }
+        return Some(result);
+    }
+
+    /// **(internal)** Divide this number by a small `divisor` in place, returning the remainder.
+    fn divide(&mut self, divisor: u32) -> u32 {
+        let mut remainder: u64 = 0;
+        for digit in self.digits.iter_mut().rev() {
+            let current = (remainder << 32) | u64::from(*digit);
+            *digit = (current / u64::from(divisor)) as u32;
+            remainder = current % u64::from(divisor);
+        }
+        self.normalize();
+        return remainder as u32;
+    }
+
+    /// **(internal)** Remove the most significant zero digits.
+    fn normalize(&mut self) {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+    }
+}
+
+impl From<u64> for ModelCount {
+    fn from(value: u64) -> Self {
+        let mut result = ModelCount {
+            digits: vec![value as u32, (value >> 32) as u32],
+        };
+        result.normalize();
+        return result;
+    }
+}
+
+impl AddAssign<&ModelCount> for ModelCount {
+    fn add_assign(&mut self, rhs: &ModelCount) {
+        if self.digits.len() < rhs.digits.len() {
+            self.digits.resize(rhs.digits.len(), 0);
+        }
+        let mut carry = 0;
+        for (i, digit) in self.digits.iter_mut().enumerate() {
+            let sum = u64::from(*digit) + u64::from(*rhs.digits.get(i).unwrap_or(&0)) + carry;
+            *digit = sum as u32;
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            self.digits.push(carry as u32);
+        }
+    }
+}
+
+impl Add<&ModelCount> for ModelCount {
+    type Output = ModelCount;
+
+    fn add(mut self, rhs: &ModelCount) -> Self::Output {
+        self += rhs;
+        return self;
+    }
+}
+
+impl Shl<usize> for &ModelCount {
+    type Output = ModelCount;
+
+    /// Multiply the count by $2^{bits}$.
+    fn shl(self, bits: usize) -> Self::Output {
+        if self.is_zero() {
+            return ModelCount::default();
+        }
+        let shift = bits % 32;
+        let mut digits = vec![0; bits / 32];
+        let mut carry = 0;
+        for digit in &self.digits {
+            let shifted = (u64::from(*digit) << shift) | carry;
+            digits.push(shifted as u32);
+            carry = shifted >> 32;
+        }
+        if carry > 0 {
+            digits.push(carry as u32);
+        }
+        return ModelCount { digits };
+    }
+}
+
+impl Shr<usize> for &ModelCount {
+    type Output = ModelCount;
+
+    /// Divide the count by $2^{bits}$ (rounding down).
+    fn shr(self, bits: usize) -> Self::Output {
+        let shift = bits % 32;
+        let mut digits: Vec<u32> = self.digits.iter().skip(bits / 32).cloned().collect();
+        if shift > 0 {
+            for i in 0..digits.len() {
+                let upper = digits.get(i + 1).map_or(0, |d| u64::from(*d) << 32);
+                digits[i] = ((upper | u64::from(digits[i])) >> shift) as u32;
+            }
+        }
+        let mut result = ModelCount { digits };
+        result.normalize();
+        return result;
+    }
+}
+
+impl PartialOrd for ModelCount {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for ModelCount {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Numbers are normalized, so a longer number is always larger.
+        return self
+            .digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()));
+    }
+}
+
+impl Display for ModelCount {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        // Split the number into base 10^9 chunks, starting from the least significant one.
+        let mut value = self.clone();
+        let mut chunks = Vec::new();
+        while !value.is_zero() {
+            chunks.push(value.divide(1_000_000_000));
+        }
+        write!(f, "{}", chunks.pop().unwrap())?;
+        for chunk in chunks.iter().rev() {
+            write!(f, "{:09}", chunk)?;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::ModelCount;
+
+    #[test]
+    fn model_count_arithmetic() {
+        assert!(ModelCount::default().is_zero());
+        assert_eq!(ModelCount::from(0), ModelCount::default());
+        assert_eq!(Some(1 << 40), ModelCount::power_of_two(40).to_u64());
+        assert_eq!(None, ModelCount::power_of_two(64).to_u64());
+        let max = ModelCount::from(u64::MAX);
+        assert_eq!(
+            ModelCount::power_of_two(64),
+            max.clone() + &ModelCount::from(1)
+        );
+        assert_eq!("18446744073709551615", max.to_string());
+        assert_eq!(
+            "1267650600228229401496703205376",
+            ModelCount::power_of_two(100).to_string()
+        );
+        assert_eq!("1000000000", ModelCount::from(1_000_000_000).to_string());
+        assert!(ModelCount::power_of_two(64) > max);
+        assert!(ModelCount::from(3) < ModelCount::from(1 << 33));
+        assert!(ModelCount::from(5) > ModelCount::from(4));
+        assert_eq!(ModelCount::power_of_two(100), &ModelCount::from(1) << 100);
+        assert_eq!(ModelCount::from(3 << 40), &ModelCount::from(3) << 40);
+        assert_eq!(
+            "3713820117856140824697372672",
+            (&ModelCount::from(3) << 90).to_string()
+        );
+        assert!((&ModelCount::default() << 10).is_zero());
+        assert_eq!(ModelCount::from(3), &(&ModelCount::from(3) << 90) >> 90);
+        assert_eq!(ModelCount::from(5), &ModelCount::from(21) >> 2);
+        assert_eq!(Some(u64::MAX >> 7), (&max >> 7).to_u64());
+        assert!((&ModelCount::power_of_two(40) >> 41).is_zero());
+    }
+}
@@ -0,0 +1,222 @@
+use super::{BoolExpr, Kleene, ModelCount, SatSolver};
+use crate::collections::bdd::BddManager;
+use crate::collections::bitvectors::BitVector;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+impl<A: Eq + Clone + Debug + Hash> BoolExpr<A> {
+    /// Compute a list of pairwise disjoint cubes over the given `atoms` which together cover
+    /// exactly the satisfying valuations of this expression. In each cube, `cube[i]` is the
+    /// value of `atoms[i]`, or `None` if the atom can have any value.
+    ///
+    /// The `atoms` do not have to contain all atoms of this expression: the remaining atoms
+    /// are projected away (i.e. a valuation of `atoms` is covered if it can be extended to a
+    /// satisfying valuation of the whole expression). The cubes are ordered lexicographically,
+    /// with `false` before `true`. Atoms are fixed in the order given by `atoms` and a cube is
+    /// reported as soon as all valuations of the remaining atoms are models, so the result
+    /// is not necessarily the smallest possible set of cubes.
+    pub fn satisfying_cubes(&self, atoms: &[A]) -> Vec<Vec<Option<bool>>> {
+        let index: HashMap<A, usize> = atoms.iter().cloned().zip(0..).collect();
+        // Atoms which are projected away are represented by `None`.
+        let indexed = self.map_atoms(|atom| index.get(atom).cloned());
+        // Solvers for the expression and its negation, used to check whether a partial
+        // valuation can be extended to a model, or whether all its extensions are models.
+        let mut solver = SatSolver::new();
+        solver.add_expr(self);
+        let mut negation = SatSolver::new();
+        negation.add_expr(&BoolExpr::Not(Box::new(self.clone())));
+        let mut cubes = Vec::new();
+        let mut values: Vec<Option<bool>> = vec![None; atoms.len()];
+        // Atoms are assigned in order; each is first tried with `false`, then with `true`.
+        let mut assigned = 0;
+        loop {
+            let expand = match indexed
+                .eval_kleene(|atom| Kleene::from(atom.and_then(|i| values[i])))
+                .to_bool()
+            {
+                Some(value) => {
+                    if value {
+                        cubes.push(values.clone());
+                    }
+                    false
+                }
+                None => {
+                    // Only continue if the current prefix can be extended to a model.
+                    let assumptions: Vec<(A, bool)> = (0..assigned)
+                        .map(|i| (atoms[i].clone(), values[i].unwrap()))
+                        .collect();
+                    if solver.solve_with_assumptions(&assumptions).is_none() {
+                        false
+                    } else if assigned == atoms.len()
+                        || negation.solve_with_assumptions(&assumptions).is_none()
+                    {
+                        cubes.push(values.clone());
+                        false
+                    } else {
+                        true
+                    }
+                }
+            };
+            if expand {
+                values[assigned] = Some(false);
+                assigned += 1;
+                continue;
+            }
+            // Backtrack to the last atom which was not tried with `true` yet.
+            while assigned > 0 && values[assigned - 1] == Some(true) {
+                assigned -= 1;
+                values[assigned] = None;
+            }
+            if assigned == 0 {
+                return cubes;
+            }
+            values[assigned - 1] = Some(true);
+        }
+    }
+
+    /// Compute all satisfying valuations of this expression (projected to the given `atoms`,
+    /// see `satisfying_cubes`) as bit vectors where bit `i` is the value of `atoms[i]`.
+    ///
+    /// The number of results can be exponential in the number of atoms; use
+    /// `satisfying_cubes` or `count_models` if you don't need the individual valuations.
+    pub fn satisfying_bit_vectors<BV: BitVector>(&self, atoms: &[A]) -> Vec<BV> {
+        let mut result = Vec::new();
+        for cube in self.satisfying_cubes(atoms) {
+            let free: Vec<usize> = (0..cube.len()).filter(|i| cube[*i].is_none()).collect();
+            let mut base = BV::empty(atoms.len());
+            for (i, value) in cube.iter().enumerate() {
+                if *value == Some(true) {
+                    base.set(i, true);
+                }
+            }
+            // Expand the don't-care positions in all possible ways.
+            for combination in 0..(1usize << free.len()) {
+                let mut vector = base.clone();
+                for (k, i) in free.iter().enumerate() {
+                    vector.set(*i, (combination >> k) & 1 == 1);
+                }
+                result.push(vector);
+            }
+        }
+        return result;
+    }
+
+    /// Count the satisfying valuations of the given `atoms` (projecting away all other atoms
+    /// of this expression, see `satisfying_cubes`).
+    ///
+    /// The models are counted using a BDD of the expression (with the remaining atoms
+    /// existentially quantified), so the number of models does not affect the running time.
+    pub fn count_models(&self, atoms: &[A]) -> ModelCount {
+        let mut index: HashMap<A, usize> = atoms.iter().cloned().zip(0..).collect();
+        // Atoms which are projected away are numbered after the counted ones.
+        let mut projected = Vec::new();
+        for atom in self.atoms() {
+            let variable = atoms.len() + projected.len();
+            if let Entry::Vacant(entry) = index.entry(atom) {
+                entry.insert(variable);
+                projected.push(variable);
+            }
+        }
+        let mut manager = BddManager::new(atoms.len() + projected.len());
+        let bdd = manager.from_expr(&self.map_atoms(|atom| index[atom]));
+        let bdd = manager.exists(bdd, &projected);
+        // The quantified variables are free in the result, so they are counted as well.
+        return &manager.count(bdd) >> projected.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::bitvectors::{BitVector, BitVector58};
+    use crate::logic::test_util::{atoms, parse};
+    use crate::logic::ModelCount;
+
+    #[test]
+    fn models_cubes() {
+        let expr = parse("a | b & c");
+        let cubes = expr.satisfying_cubes(&atoms(&["a", "b", "c"]));
+        assert_eq!(
+            vec![
+                vec![Some(false), Some(true), Some(true)],
+                vec![Some(true), None, None],
+            ],
+            cubes
+        );
+        // Unsatisfiable and valid expressions.
+        assert!(parse("a & !a").satisfying_cubes(&atoms(&["a"])).is_empty());
+        assert_eq!(
+            vec![vec![None]],
+            parse("a | !a").satisfying_cubes(&atoms(&["a"]))
+        );
+        assert_eq!(
+            vec![Vec::<Option<bool>>::new()],
+            parse("true").satisfying_cubes(&[])
+        );
+    }
+
+    #[test]
+    fn models_bit_vectors_match_truth_table() {
+        let names = atoms(&["a", "b", "c", "d"]);
+        let expr = parse("(a ^ b) & (c => d) | !a & d");
+        let table = expr.truth_table(&names);
+        let mut rows: Vec<usize> = expr
+            .satisfying_bit_vectors::<BitVector58>(&names)
+            .into_iter()
+            .map(|v| v.ones().into_iter().map(|i| 1 << i).sum())
+            .collect();
+        rows.sort();
+        assert_eq!(table.ones(), rows);
+        assert_eq!(Some(rows.len() as u64), expr.count_models(&names).to_u64());
+    }
+
+    #[test]
+    fn models_projection() {
+        let expr = parse("(a <=> x) & (b <=> x) & (c | x)");
+        // Valuations of a, b and c which can be extended with some x.
+        let names = atoms(&["a", "b", "c"]);
+        assert_eq!(Some(3), expr.count_models(&names).to_u64());
+        let mut vectors: Vec<Vec<bool>> = expr
+            .satisfying_bit_vectors::<BitVector58>(&names)
+            .into_iter()
+            .map(|v| v.values())
+            .collect();
+        vectors.sort();
+        let expected = vec![
+            vec![false, false, true],
+            vec![true, true, false],
+            vec![true, true, true],
+        ];
+        assert_eq!(expected, vectors);
+        // Extra atoms double the count.
+        assert_eq!(
+            Some(4),
+            expr.count_models(&atoms(&["a", "x", "y"])).to_u64()
+        );
+    }
+
+    #[test]
+    fn models_large_counts() {
+        let names: Vec<String> = (0..100).map(|i| format!("x{}", i)).collect();
+        let expr = parse("x0 | x1");
+        assert_eq!(
+            ModelCount::power_of_two(99) + &ModelCount::power_of_two(98),
+            expr.count_models(&names)
+        );
+        let chain = parse(&names.join(" & "));
+        assert_eq!(Some(1), chain.count_models(&names).to_u64());
+    }
+
+    #[test]
+    fn models_many_cubes() {
+        // Every pair `x{2i}, x{2i+1}` must have the parity of the projected atom `y{i % 3}`,
+        // so there are 2^43 models, and they cannot be merged into fewer cubes.
+        let names: Vec<String> = (0..80).map(|i| format!("x{}", i)).collect();
+        let pairs: Vec<String> = (0..40)
+            .map(|i| format!("(x{} ^ x{} ^ y{})", 2 * i, 2 * i + 1, i % 3))
+            .collect();
+        let expr = parse(&pairs.join(" & "));
+        assert_eq!(ModelCount::power_of_two(43), expr.count_models(&names));
+    }
+}
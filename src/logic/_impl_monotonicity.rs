@@ -0,0 +1,109 @@
+use super::{AtomEffect, BoolExpr, Monotonicity, ValuationPair};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+impl<A: Eq + Clone + Debug + Hash> BoolExpr<A> {
+    /// Determine how this expression depends on the given `atom`, together with witnesses
+    /// of the observed effects (see `AtomEffect`).
+    ///
+    /// The analysis is semantic: e.g. `b` is inessential in `a | b & !b`.
+    pub fn atom_effect(&self, atom: &A) -> AtomEffect<A> {
+        let low = self.substitute(atom, &BoolExpr::Const(false));
+        let high = self.substitute(atom, &BoolExpr::Const(true));
+        let not = |e: &BoolExpr<A>| BoolExpr::Not(Box::new(e.clone()));
+        let increasing = find_pair(BoolExpr::And(vec![not(&low), high.clone()]), atom);
+        let decreasing = find_pair(BoolExpr::And(vec![low, not(&high)]), atom);
+        let monotonicity = match (&increasing, &decreasing) {
+            (Some(_), Some(_)) => Monotonicity::NonMonotone,
+            (Some(_), None) => Monotonicity::Positive,
+            (None, Some(_)) => Monotonicity::Negative,
+            (None, None) => Monotonicity::Inessential,
+        };
+        return AtomEffect {
+            monotonicity,
+            increasing,
+            decreasing,
+        };
+    }
+
+    /// Compute `atom_effect` for every atom of this expression (in the order given by `atoms`).
+    pub fn atom_effects(&self) -> Vec<(A, AtomEffect<A>)> {
+        return self
+            .atoms()
+            .into_iter()
+            .map(|atom| {
+                let effect = self.atom_effect(&atom);
+                (atom, effect)
+            })
+            .collect();
+    }
+}
+
+/// **(internal)** Find a valuation satisfying `query` (an expression which does not depend
+/// on `atom`) and extend it to a pair of valuations with `atom` set to `false` and `true`.
+fn find_pair<A: Eq + Clone + Debug + Hash>(
+    query: BoolExpr<A>,
+    atom: &A,
+) -> Option<ValuationPair<A>> {
+    return query
+        .satisfying_valuation()
+        .map(|valuation: HashMap<A, bool>| {
+            let (mut low, mut high) = (valuation.clone(), valuation);
+            low.insert(atom.clone(), false);
+            high.insert(atom.clone(), true);
+            (low, high)
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::test_util::parse;
+    use crate::logic::Monotonicity;
+
+    #[test]
+    fn monotonicity_classification() {
+        let expr = parse("a & !b | c ^ d | e & !e");
+        let effects: Vec<(String, Monotonicity)> = expr
+            .atom_effects()
+            .into_iter()
+            .map(|(atom, effect)| (atom, effect.monotonicity))
+            .collect();
+        let expected = vec![
+            ("a", Monotonicity::Positive),
+            ("b", Monotonicity::Negative),
+            ("c", Monotonicity::NonMonotone),
+            ("d", Monotonicity::NonMonotone),
+            ("e", Monotonicity::Inessential),
+        ];
+        let expected: Vec<(String, Monotonicity)> = expected
+            .into_iter()
+            .map(|(atom, m)| (atom.to_string(), m))
+            .collect();
+        assert_eq!(expected, effects);
+        // Atoms outside of the support have no effect.
+        let effect = expr.atom_effect(&"x".to_string());
+        assert_eq!(Monotonicity::Inessential, effect.monotonicity);
+        assert!(effect.increasing.is_none() && effect.decreasing.is_none());
+    }
+
+    #[test]
+    fn monotonicity_witnesses() {
+        let expr = parse("a & !b | c ^ d");
+        for (atom, effect) in expr.atom_effects() {
+            let witnesses = vec![(effect.increasing, true), (effect.decreasing, false)];
+            for (pair, increasing) in witnesses {
+                if let Some((low, high)) = pair {
+                    assert!(!low[&atom] && high[&atom]);
+                    // The valuations differ only in `atom`.
+                    assert!(low.iter().all(|(a, v)| *a == atom || high[a] == *v));
+                    assert_eq!(4, low.len());
+                    assert_eq!(!increasing, expr.eval(|a| low[a]));
+                    assert_eq!(increasing, expr.eval(|a| high[a]));
+                }
+            }
+        }
+        let effect = expr.atom_effect(&"a".to_string());
+        assert!(effect.increasing.is_some() && effect.decreasing.is_none());
+    }
+}
@@ -0,0 +1,457 @@
+use super::{BoolExpr, BoolOp};
+use std::fmt::Debug;
+
+/// **(internal)** A literal is an atom together with its polarity (`true` for positive).
+pub(super) type Literal<A> = (A, bool);
+
+impl<A: Eq + Clone + Debug> BoolExpr<A> {
+    /// Convert this expression to negation normal form: the result contains only constants,
+    /// atoms, negated atoms and (flattened) n-ary `And`/`Or` nodes.
+    ///
+    /// Note that `Xor` and `Iff` are expanded by duplicating their operands, so nested
+    /// equivalences can increase the size of the expression exponentially.
+    pub fn to_nnf(&self) -> BoolExpr<A> {
+        return self.fold(nnf).0;
+    }
+
+    /// Convert this expression to an equivalent conjunctive normal form using distributivity.
+    ///
+    /// The result is an `And` of `Or` clauses which contain only atoms and negated atoms.
+    /// Duplicate literals are removed and tautological clauses are omitted. The size of the
+    /// result can be exponential; see `to_cnf_tseitin` for an equisatisfiable linear variant.
+    pub fn to_cnf(&self) -> BoolExpr<A> {
+        let clauses = clause_sets(&self.to_nnf(), true);
+        return BoolExpr::And(clauses.into_iter().map(|c| clause_expr(c, true)).collect());
+    }
+
+    /// Convert this expression to an equivalent disjunctive normal form using distributivity.
+    ///
+    /// The result is an `Or` of `And` cubes which contain only atoms and negated atoms.
+    /// Duplicate literals are removed and contradictory cubes are omitted.
+    pub fn to_dnf(&self) -> BoolExpr<A> {
+        let cubes = clause_sets(&self.to_nnf(), false);
+        return BoolExpr::Or(cubes.into_iter().map(|c| clause_expr(c, false)).collect());
+    }
+
+    /// Convert this expression to an equisatisfiable conjunctive normal form using the
+    /// Tseitin transformation. Every non-trivial subexpression is represented by a fresh
+    /// atom created using the `fresh` function (the caller must ensure these are distinct
+    /// from each other and from the atoms of this expression).
+    ///
+    /// Every model of the original expression extends to exactly one model of the result,
+    /// and every model of the result restricted to the original atoms is a model of the
+    /// original expression. The size of the result is linear in the size of the expression.
+    pub fn to_cnf_tseitin<F>(&self, mut fresh: F) -> BoolExpr<A>
+    where
+        F: FnMut() -> A,
+    {
+        let mut clauses = Vec::new();
+        let root = self.fold(|node, children| tseitin(node, children, &mut fresh, &mut clauses));
+        clauses.push(vec![root]);
+        return BoolExpr::And(clauses.into_iter().map(|c| clause_expr(c, true)).collect());
+    }
+
+    /// True if this expression is in negation normal form (as produced by `to_nnf`, except
+    /// that nested `And`/`Or` nodes don't have to be flattened).
+    pub fn is_nnf(&self) -> bool {
+        return self.fold(|node, children: Vec<bool>| match node {
+            BoolExpr::Const(_) | BoolExpr::Atom(_) => true,
+            BoolExpr::Not(inner) => inner.as_atom().is_some(),
+            BoolExpr::And(_) | BoolExpr::Or(_) => children.into_iter().all(|it| it),
+            BoolExpr::Op { .. } => false,
+        });
+    }
+
+    /// True if this expression is an `And` of `Or` clauses of literals.
+    pub fn is_cnf(&self) -> bool {
+        return is_normal_form(self, true);
+    }
+
+    /// True if this expression is an `Or` of `And` cubes of literals.
+    pub fn is_dnf(&self) -> bool {
+        return is_normal_form(self, false);
+    }
+
+    /// If this expression is an atom, return it.
+    pub fn as_atom(&self) -> Option<&A> {
+        return if let BoolExpr::Atom(atom) = self {
+            Some(atom)
+        } else {
+            None
+        };
+    }
+
+    /// If this expression is an atom or a negated atom, return the atom and its polarity
+    /// (`true` for a positive literal).
+    pub fn as_literal(&self) -> Option<(&A, bool)> {
+        return match self {
+            BoolExpr::Atom(atom) => Some((atom, true)),
+            BoolExpr::Not(inner) => inner.as_atom().map(|atom| (atom, false)),
+            _ => None,
+        };
+    }
+}
+
+/// **(internal)** Compute the negation normal forms of `node` and of its negation, given the
+/// negation normal forms of its children (and their negations).
+fn nnf<A: Eq + Clone + Debug>(
+    node: &BoolExpr<A>,
+    children: Vec<(BoolExpr<A>, BoolExpr<A>)>,
+) -> (BoolExpr<A>, BoolExpr<A>) {
+    let (positive, negative): (Vec<BoolExpr<A>>, Vec<BoolExpr<A>>) = children.into_iter().unzip();
+    let (mut positive, mut negative) = (positive.into_iter(), negative.into_iter());
+    return match node {
+        BoolExpr::Const(value) => (BoolExpr::Const(*value), BoolExpr::Const(!*value)),
+        BoolExpr::Atom(atom) => (
+            literal_expr((atom.clone(), true)),
+            literal_expr((atom.clone(), false)),
+        ),
+        BoolExpr::Not(_) => (negative.next().unwrap(), positive.next().unwrap()),
+        // By De Morgan laws, negation swaps conjunction and disjunction.
+        BoolExpr::And(_) => (
+            junction(true, positive.collect()),
+            junction(false, negative.collect()),
+        ),
+        BoolExpr::Or(_) => (
+            junction(false, positive.collect()),
+            junction(true, negative.collect()),
+        ),
+        BoolExpr::Op { op, .. } => {
+            let (a, b) = (positive.next().unwrap(), positive.next().unwrap());
+            let (not_a, not_b) = (negative.next().unwrap(), negative.next().unwrap());
+            match op {
+                BoolOp::And => (
+                    junction(true, vec![a, b]),
+                    junction(false, vec![not_a, not_b]),
+                ),
+                BoolOp::Or => (
+                    junction(false, vec![a, b]),
+                    junction(true, vec![not_a, not_b]),
+                ),
+                // a => b is !a | b, and !(a => b) is a & !b.
+                BoolOp::Imp => (
+                    junction(false, vec![not_a, b]),
+                    junction(true, vec![a, not_b]),
+                ),
+                // a ^ b is (a & !b) | (!a & b) and a <=> b is (a & b) | (!a & !b). Negation of
+                // one is the other.
+                BoolOp::Xor | BoolOp::Iff => {
+                    let xor = junction(
+                        false,
+                        vec![
+                            junction(true, vec![a.clone(), not_b.clone()]),
+                            junction(true, vec![not_a.clone(), b.clone()]),
+                        ],
+                    );
+                    let iff = junction(
+                        false,
+                        vec![
+                            junction(true, vec![a, b]),
+                            junction(true, vec![not_a, not_b]),
+                        ],
+                    );
+                    if *op == BoolOp::Xor {
+                        (xor, iff)
+                    } else {
+                        (iff, xor)
+                    }
+                }
+            }
+        }
+    };
+}
+
+/// **(internal)** Create an `And` (or `Or` if `conjunction` is false) node from the given
+/// arguments, merging arguments of the same kind into the result.
+fn junction<A: Eq + Clone + Debug>(conjunction: bool, args: Vec<BoolExpr<A>>) -> BoolExpr<A> {
+    let mut result = Vec::new();
+    for arg in args {
+        match arg {
+            BoolExpr::And(_) if conjunction => result.extend(arg.into_children()),
+            BoolExpr::Or(_) if !conjunction => result.extend(arg.into_children()),
+            _ => result.push(arg),
+        }
+    }
+    return if conjunction {
+        BoolExpr::And(result)
+    } else {
+        BoolExpr::Or(result)
+    };
+}
+
+fn literal_expr<A: Eq + Clone + Debug>(literal: Literal<A>) -> BoolExpr<A> {
+    let (atom, positive) = literal;
+    return if positive {
+        BoolExpr::Atom(atom)
+    } else {
+        BoolExpr::Not(Box::new(BoolExpr::Atom(atom)))
+    };
+}
+
+/// **(internal)** Build a clause (`Or` if `disjunction`) or a cube (`And`) from literals.
+fn clause_expr<A: Eq + Clone + Debug>(literals: Vec<Literal<A>>, disjunction: bool) -> BoolExpr<A> {
+    let literals = literals.into_iter().map(literal_expr).collect();
+    return if disjunction {
+        BoolExpr::Or(literals)
+    } else {
+        BoolExpr::And(literals)
+    };
+}
+
+/// **(internal)** Compute the clauses of the CNF (if `conjunctive`) or cubes of the DNF
+/// (otherwise) of an expression in negation normal form.
+///
+/// In the CNF case, the "outer" operator is `And` and the "inner" operator is `Or` (and the
+/// other way around for DNF). Arguments of the outer operator simply concatenate their sets,
+/// while for the inner operator, we need to compute the product of the argument sets.
+pub(super) fn clause_sets<A: Eq + Clone + Debug>(
+    expr: &BoolExpr<A>,
+    conjunctive: bool,
+) -> Vec<Vec<Literal<A>>> {
+    return expr.fold(|node, children: Vec<Vec<Vec<Literal<A>>>>| match node {
+        BoolExpr::And(_) | BoolExpr::Or(_) if matches!(node, BoolExpr::And(_)) == conjunctive => {
+            children.into_iter().flatten().collect()
+        }
+        BoolExpr::And(_) | BoolExpr::Or(_) => {
+            let mut result = vec![Vec::new()];
+            for arg_sets in children {
+                let mut product = Vec::new();
+                for clause in &result {
+                    for arg_clause in &arg_sets {
+                        if let Some(merged) = merge_clauses(clause, arg_clause) {
+                            product.push(merged);
+                        }
+                    }
+                }
+                result = product;
+            }
+            result
+        }
+        // A neutral element of the outer operator is an empty set of clauses, the other
+        // constant is a single empty clause.
+        BoolExpr::Const(value) if *value == conjunctive => Vec::new(),
+        BoolExpr::Const(_) => vec![Vec::new()],
+        BoolExpr::Atom(atom) => vec![vec![(atom.clone(), true)]],
+        // In negation normal form, the child is always an atom.
+        BoolExpr::Not(_) => {
+            let (atom, _) = node.as_literal().unwrap();
+            vec![vec![(atom.clone(), false)]]
+        }
+        BoolExpr::Op { .. } => panic!("Expression is not in negation normal form."),
+    });
+}
+
+/// **(internal)** Union of two sets of literals, or `None` if the result would contain
+/// complementary literals (i.e. the clause is a tautology, or the cube is a contradiction).
+fn merge_clauses<A: Eq + Clone + Debug>(
+    left: &[Literal<A>],
+    right: &[Literal<A>],
+) -> Option<Vec<Literal<A>>> {
+    let mut result = left.to_vec();
+    for (atom, positive) in right {
+        if let Some((_, existing)) = result.iter().find(|(a, _)| a == atom) {
+            if existing != positive {
+                return None;
+            }
+        } else {
+            result.push((atom.clone(), *positive));
+        }
+    }
+    return Some(result);
+}
+
+/// **(internal)** Tseitin-encode `node` into `clauses`, given the literals representing its
+/// children, and return the literal which is equivalent to `node` under the added clauses.
+fn tseitin<A, F>(
+    node: &BoolExpr<A>,
+    mut children: Vec<Literal<A>>,
+    fresh: &mut F,
+    clauses: &mut Vec<Vec<Literal<A>>>,
+) -> Literal<A>
+where
+    A: Eq + Clone + Debug,
+    F: FnMut() -> A,
+{
+    let negate = |(atom, positive): &Literal<A>| (atom.clone(), !positive);
+    return match node {
+        BoolExpr::Atom(atom) => (atom.clone(), true),
+        BoolExpr::Not(_) => negate(&children[0]),
+        BoolExpr::Const(value) => {
+            let x = fresh();
+            clauses.push(vec![(x.clone(), *value)]);
+            (x, true)
+        }
+        BoolExpr::And(_) | BoolExpr::Or(_) => {
+            let x = fresh();
+            define_junction(&x, matches!(node, BoolExpr::And(_)), children, clauses);
+            (x, true)
+        }
+        BoolExpr::Op { op, .. } => {
+            let b = children.pop().unwrap();
+            let a = children.pop().unwrap();
+            let x = fresh();
+            let (pos_x, neg_x) = ((x.clone(), true), (x.clone(), false));
+            match op {
+                BoolOp::And => define_junction(&x, true, vec![a, b], clauses),
+                BoolOp::Or => define_junction(&x, false, vec![a, b], clauses),
+                BoolOp::Imp => define_junction(&x, false, vec![negate(&a), b], clauses),
+                BoolOp::Xor | BoolOp::Iff => {
+                    // For xor, x is true iff the polarities of a and b differ. For iff, we
+                    // just negate b to obtain the same situation.
+                    let b = if *op == BoolOp::Xor { b } else { negate(&b) };
+                    let (na, nb) = (negate(&a), negate(&b));
+                    clauses.push(vec![neg_x.clone(), a.clone(), b.clone()]);
+                    clauses.push(vec![neg_x, na.clone(), nb.clone()]);
+                    clauses.push(vec![pos_x.clone(), na, b]);
+                    clauses.push(vec![pos_x, a, nb]);
+                }
+            }
+            (x, true)
+        }
+    };
+}
+
+/// **(internal)** Add clauses defining `x <=> (args[0] & args[1] & ...)` (or `|` if
+/// `conjunction` is false).
+fn define_junction<A: Eq + Clone + Debug>(
+    x: &A,
+    conjunction: bool,
+    args: Vec<Literal<A>>,
+    clauses: &mut Vec<Vec<Literal<A>>>,
+) {
+    // For conjunction: x => arg for every arg, and (all args) => x.
+    // For disjunction: arg => x for every arg, and x => (some arg).
+    let mut long_clause = vec![(x.clone(), conjunction)];
+    for (atom, positive) in args {
+        clauses.push(vec![
+            (x.clone(), !conjunction),
+            (atom.clone(), positive == conjunction),
+        ]);
+        long_clause.push((atom, positive != conjunction));
+    }
+    clauses.push(long_clause);
+}
+
+/// **(internal)** Check that `expr` is an outer junction of inner junctions of literals.
+fn is_normal_form<A: Eq + Clone + Debug>(expr: &BoolExpr<A>, conjunctive: bool) -> bool {
+    let outer = match expr {
+        BoolExpr::And(args) if conjunctive => args,
+        BoolExpr::Or(args) if !conjunctive => args,
+        _ => return false,
+    };
+    return outer.iter().all(|inner| match inner {
+        BoolExpr::Or(args) if conjunctive => args.iter().all(|it| it.as_literal().is_some()),
+        BoolExpr::And(args) if !conjunctive => args.iter().all(|it| it.as_literal().is_some()),
+        _ => false,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::BoolExpr;
+    use std::collections::HashMap;
+
+    const FORMULAS: [&str; 8] = [
+        "a & !(b | c) => d",
+        "a ^ b ^ c",
+        "(a <=> b) ^ !(c => a)",
+        "!(a <=> (b & !c))",
+        "a & true | false & b",
+        "!(a | false) <=> (b ^ true)",
+        "(a | b) & (c | d) & !a",
+        "a & !a",
+    ];
+
+    /// All valuations of the given atoms.
+    fn valuations(atoms: &[String]) -> Vec<HashMap<String, bool>> {
+        return (0..(1 << atoms.len()))
+            .map(|row: usize| {
+                let values = atoms
+                    .iter()
+                    .enumerate()
+                    .map(|(i, a)| (a.clone(), (row >> i) & 1 == 1));
+                values.collect()
+            })
+            .collect();
+    }
+
+    fn assert_equivalent(left: &BoolExpr<String>, right: &BoolExpr<String>) {
+        let mut atoms = left.atoms();
+        for atom in right.atoms() {
+            if !atoms.contains(&atom) {
+                atoms.push(atom);
+            }
+        }
+        for valuation in valuations(&atoms) {
+            let value = |atom: &String| valuation[atom];
+            assert_eq!(
+                left.eval(value),
+                right.eval(value),
+                "{} vs. {}",
+                left,
+                right
+            );
+        }
+    }
+
+    #[test]
+    fn nnf_cnf_dnf_are_equivalent() {
+        for input in FORMULAS.iter() {
+            let expr: BoolExpr<String> = input.parse().unwrap();
+            let (nnf, cnf, dnf) = (expr.to_nnf(), expr.to_cnf(), expr.to_dnf());
+            assert!(nnf.is_nnf() && cnf.is_nnf() && dnf.is_nnf());
+            assert!(cnf.is_cnf() && !cnf.is_dnf());
+            assert!(dnf.is_dnf() && !dnf.is_cnf());
+            assert!(!expr.is_nnf());
+            assert_equivalent(&expr, &nnf);
+            assert_equivalent(&expr, &cnf);
+            assert_equivalent(&expr, &dnf);
+        }
+    }
+
+    #[test]
+    fn normal_form_shapes() {
+        let expr: BoolExpr<String> = "!(a => (b ^ c))".parse().unwrap();
+        assert_eq!("a & (b & c | !b & !c)", expr.to_nnf().to_string());
+        let expr: BoolExpr<String> = "(a & b) | (a & !b) | c".parse().unwrap();
+        assert_eq!(
+            "(a | c) & (a | !b | c) & (b | a | c)",
+            expr.to_cnf().to_string()
+        );
+        let expr: BoolExpr<String> = "(a | b) & (!a | !b) & a".parse().unwrap();
+        assert_eq!("a & !b", expr.to_dnf().to_string());
+        let contradiction: BoolExpr<String> = "a & !a".parse().unwrap();
+        assert_eq!(BoolExpr::Or(vec![]), contradiction.to_dnf());
+        let tautology: BoolExpr<String> = "a | !a".parse().unwrap();
+        assert_eq!(BoolExpr::And(vec![]), tautology.to_cnf());
+    }
+
+    #[test]
+    fn tseitin_is_equisatisfiable() {
+        for input in FORMULAS.iter() {
+            let expr: BoolExpr<String> = input.parse().unwrap();
+            let mut counter = 0;
+            let cnf = expr.to_cnf_tseitin(|| {
+                counter += 1;
+                format!("t{}", counter)
+            });
+            assert!(cnf.is_cnf());
+            let fresh: Vec<String> = (1..=counter).map(|i| format!("t{}", i)).collect();
+            for valuation in valuations(&expr.atoms()) {
+                // Count extensions of the valuation which satisfy the Tseitin encoding.
+                let extensions = valuations(&fresh)
+                    .into_iter()
+                    .filter(|extension| {
+                        cnf.eval(|atom| *valuation.get(atom).or(extension.get(atom)).unwrap())
+                    })
+                    .count();
+                let expected = if expr.eval(|atom| valuation[atom]) {
+                    1
+                } else {
+                    0
+                };
+                assert_eq!(expected, extensions, "{} in {:?}", input, valuation);
+            }
+        }
+    }
+}
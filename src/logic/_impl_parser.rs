@@ -0,0 +1,409 @@
+use super::{BoolExpr, BoolOp, ParseError};
+use regex::Regex;
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+/// **(internal)** Binary operators ordered from the loosest to the tightest binding.
+pub(super) const PRECEDENCE: [BoolOp; 5] = [
+    BoolOp::Iff,
+    BoolOp::Imp,
+    BoolOp::Or,
+    BoolOp::Xor,
+    BoolOp::And,
+];
+
+thread_local! {
+    /// **(internal)** Regex matching one token (or whitespace) at the start of the input. The
+    /// capture groups are: whitespace, `<=>`, `=>`, single character symbol, identifier, number.
+    static TOKEN_REGEX: Regex = Regex::new(
+        r"^(?:(\s+)|(<=>)|(=>)|([!&|^()])|([a-zA-Z_][a-zA-Z0-9_]*)|([0-9][a-zA-Z0-9_]*))"
+    )
+    .unwrap();
+}
+
+/// **(internal)** Kinds of tokens recognized by the tokenizer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum TokenKind {
+    Identifier(String),
+    /// Constant written as `0` or `1`.
+    Const(bool),
+    Not,
+    Op(BoolOp),
+    LeftParen,
+    RightParen,
+    End,
+}
+
+/// **(internal)** A token together with its position in the source string.
+#[derive(Clone, Debug)]
+struct Token {
+    kind: TokenKind,
+    line: usize,
+    column: usize,
+}
+
+impl Display for TokenKind {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        return match self {
+            TokenKind::Identifier(name) => write!(f, "identifier `{}`", name),
+            TokenKind::Const(value) => write!(f, "`{}`", *value as u8),
+            TokenKind::Not => write!(f, "`!`"),
+            TokenKind::Op(BoolOp::And) => write!(f, "`&`"),
+            TokenKind::Op(BoolOp::Or) => write!(f, "`|`"),
+            TokenKind::Op(BoolOp::Xor) => write!(f, "`^`"),
+            TokenKind::Op(BoolOp::Imp) => write!(f, "`=>`"),
+            TokenKind::Op(BoolOp::Iff) => write!(f, "`<=>`"),
+            TokenKind::LeftParen => write!(f, "`(`"),
+            TokenKind::RightParen => write!(f, "`)`"),
+            TokenKind::End => write!(f, "end of input"),
+        };
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        return write!(
+            f,
+            "Line {}, column {}: expected {}, found {}.",
+            self.line, self.column, self.expected, self.found
+        );
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FromStr for BoolExpr<String> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return Parser::new(tokenize(s)?).parse();
+    }
+}
+
+/// **(internal)** Split the input string into a vector of tokens, terminated by `TokenKind::End`.
+fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut remaining = input;
+    let (mut line, mut column) = (1, 1);
+    while !remaining.is_empty() {
+        let captures = TOKEN_REGEX
+            .with(|regex| regex.captures(remaining))
+            .ok_or_else(|| ParseError {
+                line,
+                column,
+                expected: "a valid token".to_string(),
+                found: format!("`{}`", remaining.chars().next().unwrap()),
+            })?;
+        let kind = if captures.get(1).is_some() {
+            None
+        } else if captures.get(2).is_some() {
+            Some(TokenKind::Op(BoolOp::Iff))
+        } else if captures.get(3).is_some() {
+            Some(TokenKind::Op(BoolOp::Imp))
+        } else if let Some(symbol) = captures.get(4) {
+            Some(match symbol.as_str() {
+                "!" => TokenKind::Not,
+                "&" => TokenKind::Op(BoolOp::And),
+                "|" => TokenKind::Op(BoolOp::Or),
+                "^" => TokenKind::Op(BoolOp::Xor),
+                "(" => TokenKind::LeftParen,
+                _ => TokenKind::RightParen,
+            })
+        } else if let Some(identifier) = captures.get(5) {
+            Some(TokenKind::Identifier(identifier.as_str().to_string()))
+        } else {
+            match &captures[6] {
+                "0" => Some(TokenKind::Const(false)),
+                "1" => Some(TokenKind::Const(true)),
+                number => {
+                    return Err(ParseError {
+                        line,
+                        column,
+                        expected: "a valid token".to_string(),
+                        found: format!("`{}`", number),
+                    })
+                }
+            }
+        };
+        if let Some(kind) = kind {
+            tokens.push(Token { kind, line, column });
+        }
+        let matched = &captures[0];
+        for c in matched.chars() {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        remaining = &remaining[matched.len()..];
+    }
+    tokens.push(Token {
+        kind: TokenKind::End,
+        line,
+        column,
+    });
+    return Ok(tokens);
+}
+
+/// **(internal)** An entry of the operator stack of the `Parser`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Pending {
+    Not,
+    Paren,
+    /// A binary operator together with its `PRECEDENCE` level.
+    Binary(BoolOp, usize),
+}
+
+/// **(internal)** An operator precedence (shunting-yard) parser over a vector of tokens.
+///
+/// Operators and operands are kept in explicit stacks instead of recursion, so arbitrarily
+/// deeply nested input can be parsed.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    operators: Vec<Pending>,
+    operands: Vec<BoolExpr<String>>,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Parser {
+        return Parser {
+            tokens,
+            position: 0,
+            operators: Vec::new(),
+            operands: Vec::new(),
+        };
+    }
+
+    fn peek(&self) -> &Token {
+        return &self.tokens[self.position];
+    }
+
+    fn error(&self, expected: &str) -> ParseError {
+        let token = self.peek();
+        return ParseError {
+            line: token.line,
+            column: token.column,
+            expected: expected.to_string(),
+            found: token.kind.to_string(),
+        };
+    }
+
+    /// Parse the whole token vector. The parser alternates between expecting an operand
+    /// (possibly preceded by negations and opening parentheses) and expecting an operator
+    /// (or a closing parenthesis).
+    fn parse(mut self) -> Result<BoolExpr<String>, ParseError> {
+        let mut expect_operand = true;
+        loop {
+            let kind = self.peek().kind.clone();
+            if expect_operand {
+                match kind {
+                    TokenKind::Not => self.operators.push(Pending::Not),
+                    TokenKind::LeftParen => self.operators.push(Pending::Paren),
+                    TokenKind::Identifier(name) => {
+                        let operand = match name.as_str() {
+                            "true" => BoolExpr::Const(true),
+                            "false" => BoolExpr::Const(false),
+                            _ => BoolExpr::Atom(name),
+                        };
+                        self.push_operand(operand);
+                        expect_operand = false;
+                    }
+                    TokenKind::Const(value) => {
+                        self.push_operand(BoolExpr::Const(value));
+                        expect_operand = false;
+                    }
+                    _ => return Err(self.error("identifier, `!` or `(`")),
+                }
+            } else {
+                match kind {
+                    TokenKind::Op(op) => {
+                        let level = PRECEDENCE.iter().position(|it| *it == op).unwrap();
+                        // Implication is right-associative, so it does not reduce itself.
+                        self.reduce(|top| top > level || (top == level && op != BoolOp::Imp));
+                        self.operators.push(Pending::Binary(op, level));
+                        expect_operand = true;
+                    }
+                    TokenKind::RightParen => {
+                        self.reduce(|_| true);
+                        if self.operators.pop() != Some(Pending::Paren) {
+                            return Err(self.error("binary operator or end of input"));
+                        }
+                        // The parenthesised expression is complete, apply its negations.
+                        let inner = self.operands.pop().unwrap();
+                        self.push_operand(inner);
+                    }
+                    TokenKind::End => {
+                        self.reduce(|_| true);
+                        if !self.operators.is_empty() {
+                            return Err(self.error("`)`"));
+                        }
+                        return Ok(self.operands.pop().unwrap());
+                    }
+                    _ => {
+                        return Err(if self.operators.contains(&Pending::Paren) {
+                            self.error("`)`")
+                        } else {
+                            self.error("binary operator or end of input")
+                        });
+                    }
+                }
+            }
+            self.position += 1;
+        }
+    }
+
+    /// Push a complete operand, applying all negations directly preceding it.
+    fn push_operand(&mut self, mut operand: BoolExpr<String>) {
+        while self.operators.last() == Some(&Pending::Not) {
+            self.operators.pop();
+            operand = BoolExpr::Not(Box::new(operand));
+        }
+        self.operands.push(operand);
+    }
+
+    /// Apply pending binary operators from the top of the stack while their precedence
+    /// level satisfies `condition` (stops at a parenthesis).
+    fn reduce<F: Fn(usize) -> bool>(&mut self, condition: F) {
+        while let Some(Pending::Binary(op, level)) = self.operators.last().cloned() {
+            if !condition(level) {
+                break;
+            }
+            self.operators.pop();
+            let right = self.operands.pop().unwrap();
+            let left = self.operands.pop().unwrap();
+            self.operands.push(BoolExpr::Op {
+                op,
+                left: Box::new(left),
+                right: Box::new(right),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::test_util::{atom, not, op, parse};
+    use crate::logic::{BoolExpr, BoolOp, ParseError};
+
+    #[test]
+    fn parse_atoms_and_operators() {
+        assert_eq!(atom("a_1"), parse("  a_1 "));
+        assert_eq!(not(not(atom("x"))), parse("!!x"));
+        assert_eq!(BoolExpr::Const(true), parse("true"));
+        assert_eq!(not(BoolExpr::Const(false)), parse("!false"));
+        assert_eq!(atom("true_"), parse("true_"));
+        assert_eq!(
+            op(BoolOp::Or, BoolExpr::Const(false), atom("b")),
+            parse("0 | b")
+        );
+        assert_eq!(not(BoolExpr::Const(true)), parse("!1"));
+        assert_eq!(op(BoolOp::And, atom("a"), atom("b")), parse("a & b"));
+        assert_eq!(op(BoolOp::Or, atom("a"), atom("b")), parse("a|b"));
+        assert_eq!(op(BoolOp::Xor, atom("a"), atom("b")), parse("a ^ b"));
+        assert_eq!(op(BoolOp::Imp, atom("a"), atom("b")), parse("a => b"));
+        assert_eq!(op(BoolOp::Iff, atom("a"), atom("b")), parse("a <=> b"));
+    }
+
+    #[test]
+    fn parse_precedence_and_associativity() {
+        let (a, b, c, d) = (atom("A"), atom("B"), atom("C"), atom("D"));
+        assert_eq!(
+            op(
+                BoolOp::Imp,
+                op(
+                    BoolOp::And,
+                    a.clone(),
+                    not(op(BoolOp::Or, b.clone(), c.clone()))
+                ),
+                d.clone()
+            ),
+            parse("A & !(B | C) => D")
+        );
+        assert_eq!(
+            op(
+                BoolOp::Or,
+                a.clone(),
+                op(
+                    BoolOp::Xor,
+                    b.clone(),
+                    op(BoolOp::And, c.clone(), d.clone())
+                )
+            ),
+            parse("A | B ^ C & D")
+        );
+        assert_eq!(
+            op(
+                BoolOp::And,
+                op(BoolOp::And, a.clone(), b.clone()),
+                c.clone()
+            ),
+            parse("A & B & C")
+        );
+        assert_eq!(
+            op(
+                BoolOp::Imp,
+                a.clone(),
+                op(BoolOp::Imp, b.clone(), c.clone())
+            ),
+            parse("A => B => C")
+        );
+        assert_eq!(
+            op(
+                BoolOp::Iff,
+                op(BoolOp::Imp, a.clone(), b.clone()),
+                c.clone()
+            ),
+            parse("A => B <=> C")
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = "a &\n  (b | )".parse::<BoolExpr<String>>().unwrap_err();
+        assert_eq!(
+            ParseError {
+                line: 2,
+                column: 8,
+                expected: "identifier, `!` or `(`".to_string(),
+                found: "`)`".to_string(),
+            },
+            error
+        );
+        let error = "a b".parse::<BoolExpr<String>>().unwrap_err();
+        assert_eq!((1, 3), (error.line, error.column));
+        assert_eq!("identifier `b`", error.found);
+        let error = "a & # b".parse::<BoolExpr<String>>().unwrap_err();
+        assert_eq!((1, 5), (error.line, error.column));
+        assert_eq!("`#`", error.found);
+        let error = "a & 12".parse::<BoolExpr<String>>().unwrap_err();
+        assert_eq!(
+            ((1, 5), "`12`"),
+            ((error.line, error.column), &*error.found)
+        );
+        let error = "".parse::<BoolExpr<String>>().unwrap_err();
+        assert_eq!("end of input", error.found);
+        assert_eq!(
+            "Line 1, column 1: expected identifier, `!` or `(`, found end of input.",
+            error.to_string()
+        );
+        let error = "(a | b))".parse::<BoolExpr<String>>().unwrap_err();
+        assert_eq!((1, 8), (error.line, error.column));
+        assert_eq!("binary operator or end of input", error.expected);
+        let error = "(a b)".parse::<BoolExpr<String>>().unwrap_err();
+        assert_eq!(("`)`", 4), (&*error.expected, error.column));
+    }
+
+    #[test]
+    fn parse_deep_expression() {
+        // Display -> parse round trip of a formula nested deeper than any call stack allows.
+        let mut expr = atom("x0");
+        for i in 1..100_000 {
+            expr = op(BoolOp::Or, not(expr), atom(&format!("x{}", i)));
+        }
+        let parsed: BoolExpr<String> = expr.to_string().parse().unwrap();
+        assert_eq!(expr, parsed);
+    }
+}
@@ -0,0 +1,87 @@
+use super::BoolExpr;
+use std::fmt::Debug;
+
+impl<A: Eq + Clone + Debug> BoolExpr<A> {
+    /// Existentially quantify the given `atoms`: the result is true for a valuation of the
+    /// remaining atoms if this expression is true for *some* value of the quantified atoms.
+    ///
+    /// The quantifiers are eliminated one by one using the Shannon expansion
+    /// `exists a. f = f[a/true] | f[a/false]`, simplifying the result after every step
+    /// (see `simplify`). The result does not contain any of the quantified atoms, but it can
+    /// be exponentially larger than the original expression.
+    pub fn exists(&self, atoms: &[A]) -> BoolExpr<A> {
+        return self.quantify(atoms, false);
+    }
+
+    /// Universally quantify the given `atoms`: the result is true for a valuation of the
+    /// remaining atoms if this expression is true for *all* values of the quantified atoms.
+    ///
+    /// Same as `exists`, but using the expansion `forall a. f = f[a/true] & f[a/false]`.
+    pub fn forall(&self, atoms: &[A]) -> BoolExpr<A> {
+        return self.quantify(atoms, true);
+    }
+
+    /// **(internal)** Eliminate universal (`universal == true`) or existential quantifiers
+    /// over the given atoms using the Shannon expansion.
+    fn quantify(&self, atoms: &[A], universal: bool) -> BoolExpr<A> {
+        let mut result = self.simplify();
+        for atom in atoms {
+            let low = result.substitute(atom, &BoolExpr::Const(false));
+            if low == result {
+                // The atom does not appear in the expression.
+                continue;
+            }
+            let high = result.substitute(atom, &BoolExpr::Const(true));
+            let cofactors = vec![high, low];
+            result = if universal {
+                BoolExpr::And(cofactors)
+            } else {
+                BoolExpr::Or(cofactors)
+            }
+            .simplify();
+        }
+        return result;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::test_util::parse;
+    use crate::logic::BoolExpr;
+
+    fn names(atoms: &[&str]) -> Vec<String> {
+        return atoms.iter().map(|it| it.to_string()).collect();
+    }
+
+    #[test]
+    fn quantify_simple() {
+        let expr = parse("a & b | !a & c");
+        assert_eq!("b | c", expr.exists(&names(&["a"])).to_string());
+        assert_eq!("b & c", expr.forall(&names(&["a"])).to_string());
+        assert_eq!("true", expr.exists(&names(&["a", "b", "c"])).to_string());
+        assert_eq!("false", expr.forall(&names(&["c", "a"])).to_string());
+        // Atoms which do not appear in the expression are ignored.
+        assert_eq!("b", parse("b").exists(&names(&["a"])).to_string());
+        assert_eq!("true", parse("a | !a").forall(&names(&["a"])).to_string());
+    }
+
+    #[test]
+    fn quantify_semantics() {
+        let expr = parse("(a => b) & (b ^ c) | (a <=> d) & !c");
+        let quantified = names(&["b", "c"]);
+        let exists = expr.exists(&quantified);
+        let forall = expr.forall(&quantified);
+        assert!(exists.atoms().iter().all(|atom| !quantified.contains(atom)));
+        assert!(forall.atoms().iter().all(|atom| !quantified.contains(atom)));
+        // Compare with the explicit disjunction/conjunction of all four cofactors.
+        let cofactors: Vec<BoolExpr<String>> = (0..4)
+            .map(|i| {
+                expr.substitute(&quantified[0], &BoolExpr::Const(i & 1 == 1))
+                    .substitute(&quantified[1], &BoolExpr::Const(i & 2 == 2))
+            })
+            .collect();
+        assert!(exists.is_equivalent(&BoolExpr::Or(cofactors.clone())));
+        assert!(forall.is_equivalent(&BoolExpr::And(cofactors)));
+        assert!(forall.implies(&exists));
+    }
+}
@@ -0,0 +1,170 @@
+use super::_impl_cdcl::{literal, Cdcl};
+use super::_impl_normal_forms::{clause_sets, Literal};
+use super::{BoolExpr, SatSolver};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+impl<A: Eq + Clone + Debug + Hash> Default for SatSolver<A> {
+    fn default() -> Self {
+        return SatSolver::new();
+    }
+}
+
+impl<A: Eq + Clone + Debug + Hash> SatSolver<A> {
+    /// Create a new solver with no clauses (trivially satisfiable).
+    pub fn new() -> SatSolver<A> {
+        return SatSolver {
+            variables: HashMap::new(),
+            atoms: Vec::new(),
+            core: Cdcl::new(),
+        };
+    }
+
+    /// Require the given `expr` to be true in all models.
+    ///
+    /// Expressions in CNF are added clause by clause; other expressions are first converted
+    /// using the Tseitin transformation with fresh auxiliary variables (which never appear
+    /// in the models).
+    pub fn add_expr(&mut self, expr: &BoolExpr<A>) {
+        let indexed = expr.map_atoms(|atom| self.variable(atom));
+        let cnf = if indexed.is_cnf() {
+            indexed
+        } else {
+            let core = &mut self.core;
+            let atoms = &mut self.atoms;
+            indexed.to_cnf_tseitin(|| {
+                atoms.push(None);
+                core.new_var()
+            })
+        };
+        for clause in clause_sets(&cnf, true) {
+            self.core
+                .add_clause(clause.into_iter().map(encode).collect());
+        }
+    }
+
+    /// Require at least one of the given literals (atoms with polarities, `true` being
+    /// positive) to be true in all models. An empty clause makes the solver unsatisfiable.
+    pub fn add_clause(&mut self, literals: &[(A, bool)]) {
+        let clause = literals
+            .iter()
+            .map(|(atom, positive)| literal(self.variable(atom), *positive))
+            .collect();
+        self.core.add_clause(clause);
+    }
+
+    /// Return a model of all added formulas (a valuation of all atoms which appear in them),
+    /// or `None` if they are unsatisfiable.
+    pub fn solve(&mut self) -> Option<HashMap<A, bool>> {
+        return self.solve_with_assumptions(&[]);
+    }
+
+    /// Same as `solve`, but only considers models where the given literals are true. The
+    /// assumptions only apply to this call.
+    pub fn solve_with_assumptions(
+        &mut self,
+        assumptions: &[(A, bool)],
+    ) -> Option<HashMap<A, bool>> {
+        let assumptions: Vec<usize> = assumptions
+            .iter()
+            .map(|(atom, positive)| literal(self.variable(atom), *positive))
+            .collect();
+        if !self.core.solve(&assumptions) {
+            return None;
+        }
+        let core = &self.core;
+        let model = self
+            .atoms
+            .iter()
+            .enumerate()
+            .filter_map(|(variable, atom)| {
+                atom.as_ref()
+                    .map(|atom| (atom.clone(), core.value(variable)))
+            });
+        return Some(model.collect());
+    }
+
+    /// **(internal)** The solver variable of `atom` (created if it does not exist yet).
+    fn variable(&mut self, atom: &A) -> usize {
+        if let Some(variable) = self.variables.get(atom) {
+            return *variable;
+        }
+        let variable = self.core.new_var();
+        self.atoms.push(Some(atom.clone()));
+        self.variables.insert(atom.clone(), variable);
+        return variable;
+    }
+}
+
+/// **(internal)** Encode a literal over solver variables for the core solver.
+fn encode((variable, positive): Literal<usize>) -> usize {
+    return literal(variable, positive);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::test_util::parse;
+    use crate::logic::SatSolver;
+
+    fn literal(name: &str, positive: bool) -> (String, bool) {
+        return (name.to_string(), positive);
+    }
+
+    #[test]
+    fn sat_solver_models() {
+        let mut solver = SatSolver::new();
+        assert_eq!(Some(0), solver.solve().map(|model| model.len()));
+        let expr = parse("(a => b) & (b ^ c) & (a | c <=> d)");
+        solver.add_expr(&expr);
+        let model = solver.solve().unwrap();
+        // Auxiliary variables of the Tseitin transformation are not part of the model.
+        assert_eq!(4, model.len());
+        assert!(expr.eval(|atom| model[atom]));
+        solver.add_expr(&parse("!d"));
+        let model = solver.solve().unwrap();
+        assert!(!model["a"] && !model["c"] && model["b"]);
+        solver.add_clause(&[literal("b", false)]);
+        assert_eq!(None, solver.solve());
+    }
+
+    #[test]
+    fn sat_solver_assumptions() {
+        let mut solver = SatSolver::new();
+        solver.add_expr(&parse("(!a | b) & (!b | c) & (c | d)"));
+        let model = solver
+            .solve_with_assumptions(&[literal("a", true)])
+            .unwrap();
+        assert!(model["b"] && model["c"]);
+        assert_eq!(
+            None,
+            solver.solve_with_assumptions(&[literal("a", true), literal("c", false)])
+        );
+        let model = solver
+            .solve_with_assumptions(&[literal("c", false)])
+            .unwrap();
+        assert!(!model["a"] && !model["b"] && model["d"]);
+        // Assumptions may refer to new atoms.
+        let model = solver
+            .solve_with_assumptions(&[literal("e", true)])
+            .unwrap();
+        assert!(model["e"]);
+    }
+
+    #[test]
+    fn sat_solver_enumerates_models() {
+        // Enumerate all models by blocking every found model with a new clause.
+        let mut solver = SatSolver::new();
+        solver.add_expr(&parse("(a ^ b ^ c) & (a => d)"));
+        let mut count = 0;
+        while let Some(model) = solver.solve() {
+            count += 1;
+            let blocking: Vec<(String, bool)> = model
+                .into_iter()
+                .map(|(atom, value)| (atom, !value))
+                .collect();
+            solver.add_clause(&blocking);
+        }
+        assert_eq!(6, count);
+    }
+}
@@ -0,0 +1,162 @@
+use super::{BoolExpr, BoolOp, SatSolver};
+use crate::collections::bitvectors::BitVector;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// **(internal)** Expressions with at most this many atoms are decided by enumerating
+/// their truth table; larger expressions are decided using a `SatSolver`.
+const TRUTH_TABLE_LIMIT: usize = 10;
+
+impl<A: Eq + Clone + Debug + Hash> BoolExpr<A> {
+    /// True if there is a valuation of atoms under which this expression is true.
+    pub fn is_satisfiable(&self) -> bool {
+        return self.satisfying_valuation().is_some();
+    }
+
+    /// True if this expression is true under every valuation of atoms.
+    pub fn is_tautology(&self) -> bool {
+        return self.tautology_counterexample().is_none();
+    }
+
+    /// True if every valuation which satisfies this expression satisfies `other` as well.
+    pub fn implies(&self, other: &BoolExpr<A>) -> bool {
+        return self.implication_counterexample(other).is_none();
+    }
+
+    /// True if this expression and `other` have the same value under every valuation of atoms.
+    pub fn is_equivalent(&self, other: &BoolExpr<A>) -> bool {
+        return self.equivalence_counterexample(other).is_none();
+    }
+
+    /// A valuation of the atoms in the support of this expression which makes it true,
+    /// or `None` if the expression is unsatisfiable.
+    pub fn satisfying_valuation(&self) -> Option<HashMap<A, bool>> {
+        return find_valuation(self, true);
+    }
+
+    /// A valuation of the atoms in the support of this expression which makes it false,
+    /// or `None` if the expression is a tautology.
+    pub fn tautology_counterexample(&self) -> Option<HashMap<A, bool>> {
+        return find_valuation(self, false);
+    }
+
+    /// A valuation (of atoms in the support of both expressions) under which this expression
+    /// is true and `other` is false, or `None` if this expression implies `other`.
+    pub fn implication_counterexample(&self, other: &BoolExpr<A>) -> Option<HashMap<A, bool>> {
+        let query = BoolExpr::Op {
+            op: BoolOp::Imp,
+            left: Box::new(self.clone()),
+            right: Box::new(other.clone()),
+        };
+        return find_valuation(&query, false);
+    }
+
+    /// A valuation (of atoms in the support of both expressions) under which the two
+    /// expressions differ, or `None` if they are equivalent.
+    pub fn equivalence_counterexample(&self, other: &BoolExpr<A>) -> Option<HashMap<A, bool>> {
+        let query = BoolExpr::Op {
+            op: BoolOp::Iff,
+            left: Box::new(self.clone()),
+            right: Box::new(other.clone()),
+        };
+        return find_valuation(&query, false);
+    }
+}
+
+/// **(internal)** Find a valuation of atoms of `expr` under which it evaluates to `target`.
+fn find_valuation<A>(expr: &BoolExpr<A>, target: bool) -> Option<HashMap<A, bool>>
+where
+    A: Eq + Clone + Debug + Hash,
+{
+    let atoms = expr.atoms();
+    let values = if atoms.len() <= TRUTH_TABLE_LIMIT {
+        let table = expr.truth_table(&atoms);
+        (0..table.len())
+            .find(|row| table.get(*row) == target)
+            .map(|row| (0..atoms.len()).map(|i| (row >> i) & 1 == 1).collect())
+    } else {
+        let mut solver = SatSolver::new();
+        if target {
+            solver.add_expr(expr);
+        } else {
+            solver.add_expr(&BoolExpr::Not(Box::new(expr.clone())));
+        }
+        solver
+            .solve()
+            .map(|model| atoms.iter().map(|atom| model[atom]).collect())
+    };
+    return values.map(|values: Vec<bool>| atoms.into_iter().zip(values).collect());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::test_util::parse;
+    use crate::logic::BoolExpr;
+    use std::collections::HashMap;
+
+    /// Evaluate `expr` under a valuation which must assign all its atoms.
+    fn eval(expr: &BoolExpr<String>, valuation: &HashMap<String, bool>) -> bool {
+        return expr.eval(|atom| valuation[atom]);
+    }
+
+    /// A chain `x0 op x1 op ... op x{n-1}` (or `x{n-1} op ... op x0` if `reversed`).
+    fn chain(n: usize, op: &str, reversed: bool) -> BoolExpr<String> {
+        let mut names: Vec<String> = (0..n).map(|i| format!("x{}", i)).collect();
+        if reversed {
+            names.reverse();
+        }
+        return parse(&names.join(op));
+    }
+
+    #[test]
+    fn semantic_checks() {
+        assert!(parse("a & b").is_equivalent(&parse("b & a")));
+        assert!(parse("a => b").is_equivalent(&parse("!b => !a")));
+        assert!(!parse("a => b").is_equivalent(&parse("b => a")));
+        assert!(parse("a & b").implies(&parse("a | c")));
+        assert!(!parse("a | c").implies(&parse("a & b")));
+        assert!(parse("a | !a").is_tautology());
+        assert!(parse("(a => b) & a => b").is_tautology());
+        assert!(!parse("a | b").is_tautology());
+        assert!(parse("a ^ b").is_satisfiable());
+        assert!(!parse("a & !a").is_satisfiable());
+        assert!(parse("true").is_tautology());
+        assert!(!parse("false").is_satisfiable());
+    }
+
+    #[test]
+    fn semantic_counterexamples() {
+        let (left, right) = (parse("a => b"), parse("b => a"));
+        let valuation = left.equivalence_counterexample(&right).unwrap();
+        assert_eq!(2, valuation.len());
+        assert_ne!(eval(&left, &valuation), eval(&right, &valuation));
+        let (left, right) = (parse("a | c"), parse("a & b"));
+        let valuation = left.implication_counterexample(&right).unwrap();
+        assert!(eval(&left, &valuation) && !eval(&right, &valuation));
+        let expr = parse("a & (b | !c)");
+        assert!(eval(&expr, &expr.satisfying_valuation().unwrap()));
+        assert!(!eval(&expr, &expr.tautology_counterexample().unwrap()));
+    }
+
+    #[test]
+    fn semantic_checks_large_support() {
+        // Large enough to use the solver instead of a truth table.
+        let n = 40;
+        let expr = chain(n, " ^ ", false);
+        let valuation = expr.satisfying_valuation().unwrap();
+        assert_eq!(n, valuation.len());
+        assert!(eval(&expr, &valuation));
+        assert!(!eval(&expr, &expr.tautology_counterexample().unwrap()));
+        let conjunction = chain(n, " & ", false);
+        // Reordering the chain does not change the function.
+        assert!(conjunction.is_equivalent(&chain(n, " & ", true)));
+        assert!(!conjunction.is_equivalent(&chain(n - 1, " & ", true)));
+        assert!(!conjunction.implies(&expr));
+        assert!(conjunction.implies(&parse("x0 & x39")));
+        let valuation = conjunction.implication_counterexample(&expr).unwrap();
+        assert!(valuation.values().all(|v| *v));
+        let contradiction = BoolExpr::And(vec![conjunction, parse("!x20")]);
+        assert!(!contradiction.is_satisfiable());
+    }
+}
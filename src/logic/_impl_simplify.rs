@@ -0,0 +1,203 @@
+use super::{BoolExpr, BoolOp};
+use std::fmt::Debug;
+
+impl<A: Eq + Clone + Debug> BoolExpr<A> {
+    /// Simplify this expression using basic algebraic laws.
+    ///
+    /// The simplification performs constant folding, removes double negations, merges
+    /// nested conjunctions and disjunctions (the result uses n-ary `And`/`Or` nodes), removes
+    /// duplicate operands (idempotence), and applies the absorption (`a & (a | b) = a`) and
+    /// complementation (`a & !a = false`) laws. Trivial cases of `Xor`, `Iff` and `Imp`
+    /// (constant or equal/complementary operands) are reduced as well.
+    ///
+    /// The result is equivalent to the original expression, but it is not guaranteed to be
+    /// minimal in any sense.
+    pub fn simplify(&self) -> BoolExpr<A> {
+        return self.fold(|node, mut children| match node {
+            BoolExpr::Const(_) | BoolExpr::Atom(_) => node.clone(),
+            BoolExpr::Not(_) => negate(children.pop().unwrap()),
+            BoolExpr::And(_) => junction(true, children),
+            BoolExpr::Or(_) => junction(false, children),
+            BoolExpr::Op { op, .. } => {
+                let right = children.pop().unwrap();
+                let left = children.pop().unwrap();
+                match op {
+                    BoolOp::And => junction(true, vec![left, right]),
+                    BoolOp::Or => junction(false, vec![left, right]),
+                    BoolOp::Imp => implication(left, right),
+                    BoolOp::Xor => equivalence(left, right, false),
+                    BoolOp::Iff => equivalence(left, right, true),
+                }
+            }
+        });
+    }
+}
+
+/// **(internal)** Negate a simplified expression, folding constants and double negations.
+fn negate<A: Eq + Clone + Debug>(expr: BoolExpr<A>) -> BoolExpr<A> {
+    return match expr {
+        BoolExpr::Const(value) => BoolExpr::Const(!value),
+        BoolExpr::Not(_) => expr.into_children().pop().unwrap(),
+        _ => BoolExpr::Not(Box::new(expr)),
+    };
+}
+
+/// **(internal)** True if `left` and `right` are syntactically complementary (`x` and `!x`).
+fn complementary<A: Eq + Clone + Debug>(left: &BoolExpr<A>, right: &BoolExpr<A>) -> bool {
+    return match (left, right) {
+        (BoolExpr::Not(inner), other) | (other, BoolExpr::Not(inner)) => **inner == *other,
+        _ => false,
+    };
+}
+
+/// **(internal)** If `expr` is a junction of the given kind, return its arguments.
+fn junction_args<A: Eq + Clone + Debug>(
+    expr: &BoolExpr<A>,
+    conjunction: bool,
+) -> Option<&Vec<BoolExpr<A>>> {
+    return match expr {
+        BoolExpr::And(args) if conjunction => Some(args),
+        BoolExpr::Or(args) if !conjunction => Some(args),
+        _ => None,
+    };
+}
+
+/// **(internal)** Build a simplified conjunction (or disjunction if `conjunction` is false)
+/// of already simplified arguments.
+fn junction<A: Eq + Clone + Debug>(conjunction: bool, args: Vec<BoolExpr<A>>) -> BoolExpr<A> {
+    // `conjunction` is the neutral element, `!conjunction` is the absorbing element.
+    let mut result: Vec<BoolExpr<A>> = Vec::new();
+    let mut pending = args;
+    pending.reverse();
+    while let Some(arg) = pending.pop() {
+        match arg {
+            BoolExpr::Const(value) if value == conjunction => continue,
+            BoolExpr::Const(_) => return BoolExpr::Const(!conjunction),
+            _ => {}
+        }
+        if junction_args(&arg, conjunction).is_some() {
+            // Nested junction of the same kind is merged (its args are already simplified).
+            pending.extend(arg.into_children().into_iter().rev());
+            continue;
+        }
+        if result.iter().any(|it| complementary(it, &arg)) {
+            return BoolExpr::Const(!conjunction);
+        }
+        if !result.contains(&arg) {
+            result.push(arg);
+        }
+    }
+    // Absorption: remove every dual junction which contains some other argument as an operand.
+    let absorbed: Vec<bool> = result
+        .iter()
+        .map(|arg| match junction_args(arg, !conjunction) {
+            Some(inner) => inner.iter().any(|operand| result.contains(operand)),
+            None => false,
+        })
+        .collect();
+    let mut absorbed = absorbed.into_iter();
+    result.retain(|_| !absorbed.next().unwrap());
+    return match result.len() {
+        0 => BoolExpr::Const(conjunction),
+        1 => result.pop().unwrap(),
+        _ if conjunction => BoolExpr::And(result),
+        _ => BoolExpr::Or(result),
+    };
+}
+
+/// **(internal)** Build a simplified implication of already simplified operands.
+fn implication<A: Eq + Clone + Debug>(left: BoolExpr<A>, right: BoolExpr<A>) -> BoolExpr<A> {
+    return match (left, right) {
+        (BoolExpr::Const(false), _) | (_, BoolExpr::Const(true)) => BoolExpr::Const(true),
+        (BoolExpr::Const(true), right) => right,
+        (left, BoolExpr::Const(false)) => negate(left),
+        (left, right) if left == right => BoolExpr::Const(true),
+        // a => !a is !a and !a => a is a.
+        (left, right) if complementary(&left, &right) => right,
+        (left, right) => BoolExpr::Op {
+            op: BoolOp::Imp,
+            left: Box::new(left),
+            right: Box::new(right),
+        },
+    };
+}
+
+/// **(internal)** Build a simplified equivalence (if `iff`) or exclusive disjunction
+/// (otherwise) of already simplified operands.
+fn equivalence<A: Eq + Clone + Debug>(
+    left: BoolExpr<A>,
+    right: BoolExpr<A>,
+    iff: bool,
+) -> BoolExpr<A> {
+    return match (left, right) {
+        // `true <=> x` is `x` and `true ^ x` is `!x` (and the other way around for `false`).
+        (BoolExpr::Const(value), other) | (other, BoolExpr::Const(value)) => {
+            if value == iff {
+                other
+            } else {
+                negate(other)
+            }
+        }
+        (left, right) if left == right => BoolExpr::Const(iff),
+        (left, right) if complementary(&left, &right) => BoolExpr::Const(!iff),
+        (left, right) => BoolExpr::Op {
+            op: if iff { BoolOp::Iff } else { BoolOp::Xor },
+            left: Box::new(left),
+            right: Box::new(right),
+        },
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::BoolExpr;
+
+    fn simplify(input: &str) -> String {
+        let expr: BoolExpr<String> = input.parse().unwrap();
+        return expr.simplify().to_string();
+    }
+
+    #[test]
+    fn simplify_constants() {
+        assert_eq!("a", simplify("a & true"));
+        assert_eq!("false", simplify("a & false & b"));
+        assert_eq!("true", simplify("(a | true) & !false"));
+        assert_eq!("a | b", simplify("false | a | (false | b)"));
+        assert_eq!("true", simplify("false => a"));
+        assert_eq!("!a", simplify("a => false"));
+        assert_eq!("a", simplify("true => a"));
+        assert_eq!("!a", simplify("a ^ true"));
+        assert_eq!("a", simplify("false ^ a"));
+        assert_eq!("!a", simplify("false <=> a"));
+        assert_eq!("a", simplify("a <=> true"));
+        assert_eq!("true", simplify("!(a & false)"));
+    }
+
+    #[test]
+    fn simplify_algebraic_laws() {
+        assert_eq!("a", simplify("!!a"));
+        assert_eq!("!a", simplify("!!!a"));
+        assert_eq!("a & b", simplify("a & b & a & (b & a)"));
+        assert_eq!("a", simplify("a | a"));
+        assert_eq!("a", simplify("a & (a | b)"));
+        assert_eq!("a | c", simplify("a | (b & a) | c"));
+        assert_eq!("false", simplify("a & b & !a"));
+        assert_eq!("true", simplify("!(x & y) | b | x & y"));
+        assert_eq!("false", simplify("a ^ a"));
+        assert_eq!("true", simplify("!a ^ a"));
+        assert_eq!("true", simplify("a <=> a"));
+        assert_eq!("true", simplify("a => a"));
+        assert_eq!("!a", simplify("a => !a"));
+        assert_eq!("a => b", simplify("a & true => b | false"));
+        assert_eq!("c", simplify("(a & !a | c) & (c | d & (true ^ d))"));
+    }
+
+    #[test]
+    fn simplify_substituted_function() {
+        // A typical situation after fixing an input `b` to true.
+        let expr: BoolExpr<String> = "a & !true | (c ^ (true | d)) & (true => e)"
+            .parse()
+            .unwrap();
+        assert_eq!("!c & e", expr.simplify().to_string());
+    }
+}
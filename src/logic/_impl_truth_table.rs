@@ -0,0 +1,123 @@
+use super::BoolExpr;
+use crate::collections::bitvectors::{ArrayBitVector, BitVector};
+use crate::IdState;
+use std::fmt::Debug;
+
+impl<A: Eq + Clone + Debug> BoolExpr<A> {
+    /// Compute the truth table of this expression over the given `atoms`.
+    ///
+    /// The result has $2^n$ entries (for $n$ atoms). The value at index `row` is the value
+    /// of the expression when each `atoms[i]` is set to `IdState::from(row).get_bit(i)`. That
+    /// is, the first atom corresponds to the least significant bit of the row index.
+    ///
+    /// Panics if the expression contains an atom which is not in `atoms`.
+    pub fn truth_table(&self, atoms: &[A]) -> ArrayBitVector {
+        if atoms.len() >= std::mem::size_of::<usize>() * 8 {
+            panic!("Cannot build a truth table for {} atoms.", atoms.len());
+        }
+        let indexed = self.map_atoms(|atom| {
+            atoms
+                .iter()
+                .position(|it| it == atom)
+                .unwrap_or_else(|| panic!("Atom {:?} is not in the truth table.", atom))
+        });
+        let rows = 1 << atoms.len();
+        let mut table = ArrayBitVector::empty(rows);
+        for row in 0..rows {
+            if indexed.eval_id_state(IdState::from(row)) {
+                table.set(row, true);
+            }
+        }
+        return table;
+    }
+
+    /// Build the canonical disjunctive normal form of the function given by a truth table
+    /// over the given `atoms` (see `truth_table` for the description of the table layout).
+    ///
+    /// The result is an `Or` of `And` minterms (one for every `true` row, in increasing
+    /// order), each containing a literal for every atom (in the order given by `atoms`).
+    ///
+    /// Panics if the length of the table is not $2^n$ for $n$ atoms.
+    pub fn from_truth_table<BV: BitVector>(table: &BV, atoms: &[A]) -> BoolExpr<A> {
+        if atoms.len() >= std::mem::size_of::<usize>() * 8 || table.len() != 1 << atoms.len() {
+            panic!(
+                "Truth table of length {} does not match {} atoms.",
+                table.len(),
+                atoms.len()
+            );
+        }
+        let minterm = |row: usize| {
+            let state = IdState::from(row);
+            let literals = atoms.iter().enumerate().map(|(i, atom)| {
+                let atom = BoolExpr::Atom(atom.clone());
+                if state.get_bit(i) {
+                    atom
+                } else {
+                    BoolExpr::Not(Box::new(atom))
+                }
+            });
+            BoolExpr::And(literals.collect())
+        };
+        return BoolExpr::Or(table.ones().into_iter().map(minterm).collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::bitvectors::{ArrayBitVector, BitVector, BitVector58};
+    use crate::logic::test_util::{atoms, parse};
+    use crate::logic::BoolExpr;
+    use std::collections::HashSet;
+
+    #[test]
+    fn truth_table_layout() {
+        let table = parse("a & !b").truth_table(&atoms(&["a", "b"]));
+        assert_eq!(vec![1], table.ones());
+        let table = parse("a & !b").truth_table(&atoms(&["b", "a"]));
+        assert_eq!(vec![2], table.ones());
+        let table = parse("a ^ b | c").truth_table(&atoms(&["a", "b", "c"]));
+        assert_eq!(vec![1, 2, 4, 5, 6, 7], table.ones());
+        // Atoms which do not appear in the expression are allowed.
+        let table = parse("true").truth_table(&atoms(&["x"]));
+        assert_eq!(vec![0, 1], table.ones());
+        assert_eq!(1, parse("false").truth_table(&[]).len());
+    }
+
+    #[test]
+    fn truth_table_is_canonical() {
+        let names = atoms(&["a", "b", "c"]);
+        let equivalent = [
+            "a & b | c",
+            "c | b & a",
+            "!(!c & (!a | !b))",
+            "(a | c) & (b | c)",
+        ];
+        let mut tables = HashSet::new();
+        for input in equivalent.iter() {
+            tables.insert(parse(input).truth_table(&names));
+        }
+        tables.insert(parse("a | b | c").truth_table(&names));
+        assert_eq!(2, tables.len());
+    }
+
+    #[test]
+    fn truth_table_round_trip() {
+        let names = atoms(&["a", "b", "c"]);
+        let expr = parse("a & !(b | c) => c ^ a");
+        let table = expr.truth_table(&names);
+        let dnf = BoolExpr::from_truth_table(&table, &names);
+        assert!(dnf.is_dnf());
+        assert_eq!(table, dnf.truth_table(&names));
+        let small = BitVector58::from(table.values());
+        assert_eq!(dnf, BoolExpr::from_truth_table(&small, &names));
+        let minterms =
+            BoolExpr::from_truth_table(&ArrayBitVector::from_ones(4, vec![1]), &names[..2]);
+        assert_eq!("a & !b", minterms.to_string());
+    }
+
+    #[test]
+    #[should_panic]
+    fn truth_table_missing_atom() {
+        parse("a & b").truth_table(&atoms(&["a"]));
+    }
+}
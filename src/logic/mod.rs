@@ -1,12 +1,319 @@
 //! General constructs used when working with logical formulas. Right now mostly
 //! focused on boolean formulas.
 //!
+//! ### Parsing
 //!
+//! A `BoolExpr<String>` can be parsed from its textual representation. Atoms are
+//! identifiers (`[a-zA-Z_][a-zA-Z0-9_]*`), except for `true` and `false` which denote
+//! constants (constants can be also written as `1` and `0`). The supported operators are
+//! (from the tightest to the loosest binding) `!`, `&`, `^`, `|`, `=>` and `<=>`.
+//! Implication is right-associative, all other binary operators are left-associative.
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::{BoolExpr, BoolOp};
+//! let expr: BoolExpr<String> = "A & !(B | C) => D".parse().unwrap();
+//! if let BoolExpr::Op { op, .. } = &expr {
+//!     assert_eq!(BoolOp::Imp, *op);
+//! }
+//! // Errors report the position of the offending token.
+//! let error = "A & (B | C".parse::<BoolExpr<String>>().unwrap_err();
+//! assert_eq!((1, 11), (error.line, error.column));
+//! ```
+//!
+//! ### Printing
+//!
+//! `BoolExpr` implements `Display` (as long as its atoms do) using the same syntax that is
+//! accepted by the parser, with parentheses added only where precedence or associativity
+//! requires them. Other notations are available through `BoolExpr::printer`:
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::{BoolExpr, Syntax};
+//! let expr: BoolExpr<String> = "(a & b) | !(c ^ d)".parse().unwrap();
+//! assert_eq!("a & b | !(c ^ d)", expr.to_string());
+//! assert_eq!("a and b or not (c xor d)", expr.printer(Syntax::Words).to_string());
+//! assert_eq!(
+//!     "a \\land b \\lor \\neg (c \\oplus d)",
+//!     expr.printer(Syntax::Latex).to_string()
+//! );
+//! // The default output can be parsed back.
+//! assert_eq!(expr, expr.to_string().parse().unwrap());
+//! ```
+//!
+//! Note that n-ary `And`/`Or` nodes are printed as chains of binary operators and are
+//! therefore parsed back in their binary form. The results are equal after `flatten`.
+//!
+//! ### Evaluation
+//!
+//! Any `BoolExpr` can be evaluated given a function which assigns values to its atoms.
+//! Expressions with `usize` atoms can be also evaluated directly in a `BitVector` or
+//! an `IdState`, in which case the atoms are treated as indices of the individual bits.
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::{BoolExpr, BoolOp};
+//! use biodivine_lib_std::collections::bitvectors::{BitVector, BitVector58};
+//! use biodivine_lib_std::IdState;
+//! let expr: BoolExpr<String> = "a & !b".parse().unwrap();
+//! assert!(expr.eval(|atom| atom == "a"));
+//! let expr = BoolExpr::Op {
+//!     op: BoolOp::And,
+//!     left: Box::new(BoolExpr::Atom(0)),
+//!     right: Box::new(BoolExpr::Not(Box::new(BoolExpr::Atom(1)))),
+//! };
+//! assert!(expr.eval_bit_vector(&BitVector58::from_ones(2, vec![0])));
+//! assert!(!expr.eval_id_state(IdState::from(0b11)));
+//! ```
+//!
+//! When some atoms are not known, the expression can be evaluated in Kleene's three-valued
+//! logic using `eval_kleene`. This is sound (a `True`/`False` result holds for every value
+//! of the unknown atoms), but not complete: `a | !a` is `Unknown` when `a` is unknown.
+//! The exact answer is given by `eval_definite`, which is `Unknown` only if both values
+//! of the expression are actually possible.
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::{BoolExpr, Kleene};
+//! let expr: BoolExpr<String> = "(a | b) & (a | !b)".parse().unwrap();
+//! let valuation = |atom: &String| match atom.as_str() {
+//!     "a" => Kleene::False,
+//!     _ => Kleene::Unknown,
+//! };
+//! assert_eq!(Kleene::Unknown, expr.eval_kleene(valuation));
+//! assert_eq!(Kleene::False, expr.eval_definite(valuation));
+//! assert_eq!(Kleene::True, expr.eval_kleene(|_| Kleene::True));
+//! ```
+//!
+//! ### Normal forms
+//!
+//! Expressions can be converted to negation, conjunctive and disjunctive normal form.
+//! The results use n-ary `And`/`Or` nodes and contain no `Xor`, `Iff` or `Imp`. Apart from
+//! the (possibly exponential) distributive `to_cnf`, an equisatisfiable CNF can be also
+//! obtained using the Tseitin transformation with fresh atoms:
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::BoolExpr;
+//! let expr: BoolExpr<String> = "!(a => (b ^ c))".parse().unwrap();
+//! assert_eq!("a & (b & c | !b & !c)", expr.to_nnf().to_string());
+//! assert_eq!("a & (b | !c) & (c | !b)", expr.to_cnf().to_string());
+//! assert!(expr.to_dnf().is_dnf());
+//! let mut fresh = 0;
+//! let tseitin = expr.to_cnf_tseitin(|| {
+//!     fresh += 1;
+//!     format!("_t{}", fresh)
+//! });
+//! assert!(tseitin.is_cnf());
+//! ```
+//!
+//! ### Simplification
+//!
+//! `BoolExpr::simplify` removes constants, double negations, duplicate operands and
+//! trivially absorbed or complementary subterms:
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::BoolExpr;
+//! let expr: BoolExpr<String> = "a & !true | (c ^ (true | d)) & !!(a | a & b)".parse().unwrap();
+//! assert_eq!("!c & a", expr.simplify().to_string());
+//! ```
+//!
+//! ### Quantification
+//!
+//! Atoms can be existentially (`exists`) or universally (`forall`) quantified. The quantifiers
+//! are eliminated using the Shannon expansion, so the result is again a plain `BoolExpr`
+//! which does not contain the quantified atoms:
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::BoolExpr;
+//! let expr: BoolExpr<String> = "(a => b) & (b => c)".parse().unwrap();
+//! let b = vec!["b".to_string()];
+//! assert_eq!("c | !a", expr.exists(&b).to_string());
+//! assert_eq!("c & !a", expr.forall(&b).to_string());
+//! ```
+//!
+//! ### Atoms and traversal
+//!
+//! Atoms can be renamed (`map_atoms`), replaced by other expressions (`substitute`) and
+//! collected (`support`, `atoms`). All of these are implemented using `BoolExpr::fold`,
+//! a bottom-up traversal which can be also used to implement other custom operations.
+//! For more control, implement `BoolExprFold` (with pre- and post-order hooks) or
+//! `BoolExprVisitor`. Both traversals use an explicit stack instead of recursion. The same
+//! holds for parsing, printing, cloning, comparing and dropping, so all of these work even
+//! for expressions which are nested hundreds of thousands of levels deep.
+//!
+//! ```rust
+//! use biodivine_lib_std::collections::sets::{ElementSet, ExplicitSet};
+//! use biodivine_lib_std::logic::BoolExpr;
+//! let expr: BoolExpr<String> = "a & (b | !c)".parse().unwrap();
+//! let support = expr.support();
+//! assert!(support.contains(&"a".to_string()) && !support.contains(&"d".to_string()));
+//! let inlined = expr.substitute(&"b".to_string(), &"x ^ y".parse().unwrap());
+//! assert_eq!("a & (x ^ y | !c)", inlined.to_string());
+//! let depth = expr.fold(|_, children: Vec<usize>| 1 + children.into_iter().max().unwrap_or(0));
+//! assert_eq!(4, depth);
+//! ```
+//!
+//! ### Shared formulas
+//!
+//! A `FormulaArena` stores formulas as a DAG where every distinct subformula appears only
+//! once. Identical subformulas are thus represented by the same `FormulaId` (so they can be
+//! compared in constant time) and transformations can be memoized using `FormulaArena::fold`.
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::{BoolExpr, FormulaArena};
+//! let mut arena = FormulaArena::new();
+//! let expr: BoolExpr<String> = "(a & b) | !(a & b)".parse().unwrap();
+//! let root = arena.add(&expr);
+//! // a, b, a & b, !(a & b) and the disjunction.
+//! assert_eq!(5, arena.len());
+//! assert_eq!(arena.add(&"a & b".parse().unwrap()), arena.add(&"a & b".parse().unwrap()));
+//! assert_eq!(expr, arena.to_expr(root));
+//! ```
+//!
+//! ### Truth tables
+//!
+//! For functions of a few atoms, the truth table (stored in an `ArrayBitVector`) gives a
+//! canonical representation which can be compared or hashed. Rows of the table are indexed
+//! consistently with `IdState`: the value of the `i`-th atom in row `r` is
+//! `IdState::from(r).get_bit(i)`.
+//!
+//! ```rust
+//! use biodivine_lib_std::collections::bitvectors::BitVector;
+//! use biodivine_lib_std::logic::BoolExpr;
+//! let atoms = vec!["a".to_string(), "b".to_string()];
+//! let expr: BoolExpr<String> = "!(a => b)".parse().unwrap();
+//! let table = expr.truth_table(&atoms);
+//! assert_eq!(vec![1], table.ones());
+//! assert_eq!("a & !b", BoolExpr::from_truth_table(&table, &atoms).to_string());
+//! ```
+//!
+//! ### Minimization
+//!
+//! `minimize` computes a small equivalent DNF (using the Quine–McCluskey method for
+//! expressions with a few atoms, and irredundant otherwise). A DNF can be also minimized
+//! directly from a truth table using `BoolExpr::minimize_truth_table`. The search for
+//! a minimal cover is bounded, so the result is exactly minimal only for functions with
+//! a reasonably small number of prime implicants.
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::BoolExpr;
+//! let expr: BoolExpr<String> = "a & b | a & !b & c | !(a | !c)".parse().unwrap();
+//! assert_eq!("a & b | c", expr.minimize().to_string());
+//! ```
+//!
+//! ### Semantic checks
+//!
+//! Equality of `BoolExpr` values is structural. Semantic relationships are decided by
+//! `is_equivalent`, `implies`, `is_tautology` and `is_satisfiable`. Each check has
+//! a variant which returns a witness valuation (e.g. `equivalence_counterexample`).
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::BoolExpr;
+//! let left: BoolExpr<String> = "a & b".parse().unwrap();
+//! let right: BoolExpr<String> = "b & a".parse().unwrap();
+//! assert_ne!(left, right);
+//! assert!(left.is_equivalent(&right));
+//! let weaker: BoolExpr<String> = "a | b".parse().unwrap();
+//! assert!(left.implies(&weaker));
+//! let valuation = weaker.implication_counterexample(&left).unwrap();
+//! assert_ne!(valuation["a"], valuation["b"]);
+//! ```
+//!
+//! ### SAT solving
+//!
+//! `SatSolver` is an incremental CDCL solver (with clause learning, watched literals and
+//! restarts). Formulas can be added between calls to `solve`, and each call can be restricted
+//! by assumptions. Models only assign the atoms of the added formulas.
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::{BoolExpr, SatSolver};
+//! let mut solver = SatSolver::new();
+//! solver.add_expr(&"(a => b) & (b => c)".parse::<BoolExpr<String>>().unwrap());
+//! let model = solver.solve_with_assumptions(&[("a".to_string(), true)]).unwrap();
+//! assert!(model["b"] && model["c"]);
+//! solver.add_clause(&[("c".to_string(), false)]);
+//! assert_eq!(None, solver.solve_with_assumptions(&[("a".to_string(), true)]));
+//! assert!(solver.solve().is_some());
+//! ```
+//!
+//! ### Enumeration and counting
+//!
+//! Satisfying valuations can be enumerated as disjoint cubes (with `None` for don't-care
+//! atoms) or as bit vectors, and counted exactly using `ModelCount`. All these functions take
+//! the list of atoms to enumerate; other atoms of the expression are projected away.
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::BoolExpr;
+//! let expr: BoolExpr<String> = "a | b & c".parse().unwrap();
+//! let atoms = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+//! let cubes = expr.satisfying_cubes(&atoms);
+//! assert_eq!(vec![Some(true), None, None], cubes[1]);
+//! assert_eq!("5", expr.count_models(&atoms).to_string());
+//! // Projected onto `b` and `c`, every valuation can be extended to a model.
+//! assert_eq!(Some(4), expr.count_models(&atoms[1..]).to_u64());
+//! ```
+//!
+//! ### Monotonicity
+//!
+//! `atom_effects` determines for every atom whether the expression is positive-monotone
+//! (an activator), negative-monotone (an inhibitor), non-monotone or inessential in that atom,
+//! together with pairs of valuations witnessing the observed effects.
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::{BoolExpr, Monotonicity};
+//! let expr: BoolExpr<String> = "a & !b | c & !c".parse().unwrap();
+//! let effects: Vec<Monotonicity> = expr
+//!     .atom_effects()
+//!     .into_iter()
+//!     .map(|(_, effect)| effect.monotonicity)
+//!     .collect();
+//! let expected = vec![Monotonicity::Positive, Monotonicity::Negative, Monotonicity::Inessential];
+//! assert_eq!(expected, effects);
+//! let (low, high) = expr.atom_effect(&"b".to_string()).decreasing.unwrap();
+//! assert!(expr.eval(|atom| low[atom]) && !expr.eval(|atom| high[atom]));
+//! ```
+//!
+//! ### DIMACS
+//!
+//! Expressions in CNF can be exchanged with external SAT tools using the DIMACS format.
+//! Written files carry the names of atoms in `c var <index> <name>` comment lines, which can
+//! be read back using `BoolExpr::from_dimacs_with_names`.
+//!
+//! ```rust
+//! use biodivine_lib_std::logic::BoolExpr;
+//! let expr: BoolExpr<String> = "(a | !b) & b".parse().unwrap();
+//! let dimacs = expr.to_cnf().to_dimacs();
+//! assert_eq!("c var 1 a\nc var 2 b\np cnf 2 2\n1 -2 0\n2 0\n", dimacs);
+//! let (read, names) = BoolExpr::from_dimacs_with_names(&dimacs).unwrap();
+//! assert_eq!("(a | !b) & b", read.map_atoms(|atom| names[atom].clone()).to_string());
+//! // Parse errors report the line where the problem was found.
+//! assert_eq!(2, BoolExpr::from_dimacs("p cnf 1 1\n2 0").unwrap_err().line);
+//! ```
 
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::hash::Hash;
+
+mod _impl_atoms;
+mod _impl_cdcl;
+mod _impl_dimacs;
+mod _impl_display;
+mod _impl_eval;
+mod _impl_flatten;
+mod _impl_fold;
+mod _impl_formula_arena;
+mod _impl_minimize;
+mod _impl_model_count;
+mod _impl_models;
+mod _impl_monotonicity;
+mod _impl_normal_forms;
+mod _impl_parser;
+mod _impl_quantifiers;
+mod _impl_sat_solver;
+mod _impl_semantics;
+mod _impl_simplify;
+mod _impl_truth_table;
+#[cfg(test)]
+mod test_util;
 
 /// Enumeration of supported binary boolean operations.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum BoolOp {
     And,
     Or,
@@ -15,9 +322,28 @@ pub enum BoolOp {
     Imp,
 }
 
+/// A truth value of Kleene's strong three-valued logic, where `Unknown` stands for a value
+/// which can be either `true` or `false`. See `BoolExpr::eval_kleene`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Kleene {
+    False,
+    True,
+    Unknown,
+}
+
 /// A representation of a boolean formula with generic atomic propositions.
-#[derive(Clone, Debug, Eq, PartialEq)]
+///
+/// Conjunctions and disjunctions can be represented either using binary `Op` nodes, or using
+/// n-ary `And`/`Or` nodes (see `BoolExpr::flatten` and `BoolExpr::unflatten` for conversions
+/// between the two). An empty `And` is `true` and an empty `Or` is `false`.
+///
+/// `Clone`, `PartialEq` and `Drop` are implemented without recursion, so that very deep
+/// expressions can be safely copied, compared and dropped. Only the derived `Debug` is
+/// recursive, so use `Display` to print expressions which are nested hundreds of thousands
+/// of levels deep.
+#[derive(Debug, Eq)]
 pub enum BoolExpr<A: Eq + Clone + Debug> {
+    Const(bool),
     Atom(A),
     Not(Box<BoolExpr<A>>),
     Op {
@@ -25,4 +351,163 @@ pub enum BoolExpr<A: Eq + Clone + Debug> {
         left: Box<BoolExpr<A>>,
         right: Box<BoolExpr<A>>,
     },
+    And(Vec<BoolExpr<A>>),
+    Or(Vec<BoolExpr<A>>),
+}
+
+/// An error produced when a `BoolExpr` cannot be parsed from a string.
+///
+/// Both `line` and `column` are indexed from one and point to the first character of
+/// the unexpected token (or to the end of input if the input ended prematurely).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    /// Human readable description of what the parser expected at this position.
+    pub expected: String,
+    /// The token that was actually found at this position.
+    pub found: String,
+}
+
+/// Notations in which a `BoolExpr` can be printed using a `BoolExprPrinter`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Syntax {
+    /// The default notation (`!`, `&`, `|`, `^`, `=>`, `<=>`) which is also accepted by the parser.
+    Symbolic,
+    /// Operators written as words (`not`, `and`, `or`, `xor`, `implies`, `iff`).
+    Words,
+    /// LaTeX math mode notation (`\neg`, `\land`, `\lor`, `\oplus`, ...).
+    Latex,
+}
+
+/// A `Display` wrapper which prints a `BoolExpr` in the chosen `Syntax`. Create one
+/// using `BoolExpr::printer`.
+pub struct BoolExprPrinter<'a, A: Eq + Clone + Debug> {
+    expr: &'a BoolExpr<A>,
+    syntax: Syntax,
+}
+
+/// A visitor which can be used to inspect a `BoolExpr` using `BoolExpr::visit`.
+///
+/// The traversal uses an explicit stack, so it works for arbitrarily deep expressions.
+pub trait BoolExprVisitor<A: Eq + Clone + Debug> {
+    /// Called when `expr` is reached (pre-order). If `false` is returned, the children
+    /// of `expr` are not visited (`exit` is still called though).
+    fn enter(&mut self, _expr: &BoolExpr<A>) -> bool {
+        return true;
+    }
+
+    /// Called once all children of `expr` were visited (post-order).
+    fn exit(&mut self, _expr: &BoolExpr<A>) {}
+}
+
+/// A bottom-up computation over a `BoolExpr`, evaluated using `BoolExpr::fold_with`.
+///
+/// The traversal uses an explicit stack, so it works for arbitrarily deep expressions.
+/// For simple cases, `BoolExpr::fold` accepts just a closure which acts as `exit`.
+pub trait BoolExprFold<A: Eq + Clone + Debug> {
+    type Output;
+
+    /// Called when `expr` is reached (pre-order). If a value is returned, it is used as the
+    /// result for `expr` and its children are not visited at all.
+    fn enter(&mut self, _expr: &BoolExpr<A>) -> Option<Self::Output> {
+        return None;
+    }
+
+    /// Compute the result for `expr` given the results of its children (post-order). The
+    /// results are in the same order as the subexpressions returned by `BoolExpr::children`.
+    fn exit(&mut self, expr: &BoolExpr<A>, children: Vec<Self::Output>) -> Self::Output;
+}
+
+/// An identifier of a formula interned in a `FormulaArena`.
+///
+/// Within one arena, two ids are equal if and only if the formulas they represent
+/// are structurally equal.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FormulaId(usize);
+
+/// A single node of a formula stored in a `FormulaArena`. Same as `BoolExpr`, but the
+/// children are referenced using `FormulaId`s.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum FormulaNode<A: Eq + Clone + Debug + Hash> {
+    Const(bool),
+    Atom(A),
+    Not(FormulaId),
+    Op {
+        op: BoolOp,
+        left: FormulaId,
+        right: FormulaId,
+    },
+    And(Vec<FormulaId>),
+    Or(Vec<FormulaId>),
+}
+
+/// A hash-consing storage of formulas: every distinct subformula is stored only once and
+/// is identified by a `FormulaId`. The formulas thus form a directed acyclic graph where
+/// identical subformulas are shared.
+///
+/// Formulas are never removed from the arena.
+#[derive(Clone, Debug)]
+pub struct FormulaArena<A: Eq + Clone + Debug + Hash> {
+    nodes: Vec<FormulaNode<A>>,
+    index: HashMap<FormulaNode<A>, FormulaId>,
+}
+
+/// An incremental SAT solver for formulas over atoms of type `A`.
+///
+/// Formulas are added using `add_expr` (converted to clauses using the Tseitin
+/// transformation unless they already are in CNF) or `add_clause`. Satisfiability can then
+/// be decided repeatedly, optionally under assumptions, while more formulas are being added.
+/// Found models are mapped back to the original atoms.
+#[derive(Clone, Debug)]
+pub struct SatSolver<A: Eq + Clone + Debug + Hash> {
+    /// Solver variable of every atom.
+    variables: HashMap<A, usize>,
+    /// The atom of every solver variable (`None` for auxiliary Tseitin variables).
+    atoms: Vec<Option<A>>,
+    core: _impl_cdcl::Cdcl,
+}
+
+/// An arbitrarily large non-negative integer, used to report exact numbers of models
+/// (which often exceed the range of `u64`).
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ModelCount {
+    /// Little-endian base $2^{32}$ digits, without leading zeros (zero has no digits).
+    digits: Vec<u32>,
+}
+
+/// How the value of a Boolean function depends on one of its atoms. For an update function
+/// of a regulatory network, this corresponds to the regulation being an activation, an
+/// inhibition, a dual regulation, or no (observable) regulation at all.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Monotonicity {
+    /// Setting the atom to true never makes the function false (and sometimes makes it true).
+    Positive,
+    /// Setting the atom to true never makes the function true (and sometimes makes it false).
+    Negative,
+    /// The function can both increase and decrease when the atom becomes true.
+    NonMonotone,
+    /// The function does not depend on the atom.
+    Inessential,
+}
+
+/// Two valuations which differ only in one atom: it is `false` in the first valuation and
+/// `true` in the second one.
+pub type ValuationPair<A> = (HashMap<A, bool>, HashMap<A, bool>);
+
+/// The effect of an atom on the value of a `BoolExpr`, as computed by `BoolExpr::atom_effect`.
+///
+/// The witnesses assign all atoms of the expression. A missing witness proves that the
+/// corresponding effect never occurs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AtomEffect<A: Eq + Hash> {
+    pub monotonicity: Monotonicity,
+    /// A pair of valuations where the expression is `false` for the first and `true` for
+    /// the second one (i.e. the atom activates the function). This refutes negative
+    /// monotonicity and inessentiality.
+    pub increasing: Option<ValuationPair<A>>,
+    /// A pair of valuations where the expression is `true` for the first and `false` for
+    /// the second one (i.e. the atom inhibits the function). This refutes positive
+    /// monotonicity and inessentiality.
+    pub decreasing: Option<ValuationPair<A>>,
 }
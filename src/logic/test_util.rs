@@ -0,0 +1,29 @@
+//! **(internal)** Helpers shared by the tests of this module.
+
+use crate::logic::{BoolExpr, BoolOp};
+
+/// **(internal)** Parse an expression which is known to be valid.
+pub(super) fn parse(input: &str) -> BoolExpr<String> {
+    return input.parse().unwrap();
+}
+
+pub(super) fn atom(name: &str) -> BoolExpr<String> {
+    return BoolExpr::Atom(name.to_string());
+}
+
+pub(super) fn not(inner: BoolExpr<String>) -> BoolExpr<String> {
+    return BoolExpr::Not(Box::new(inner));
+}
+
+pub(super) fn op(op: BoolOp, left: BoolExpr<String>, right: BoolExpr<String>) -> BoolExpr<String> {
+    return BoolExpr::Op {
+        op,
+        left: Box::new(left),
+        right: Box::new(right),
+    };
+}
+
+/// **(internal)** Convert atom names to owned strings.
+pub(super) fn atoms(names: &[&str]) -> Vec<String> {
+    return names.iter().map(|it| it.to_string()).collect();
+}
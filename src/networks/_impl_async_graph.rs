@@ -0,0 +1,120 @@
+use super::{AsyncEdgeIterator, AsyncEdges, AsyncGraph};
+use crate::graph::{EvolutionOperator, Graph};
+use crate::{IdState, IdStateRange};
+
+impl<'a> Graph for AsyncGraph<'a> {
+    type State = IdState;
+    type States = IdStateRange;
+    type FwdEdges = AsyncEdges<'a>;
+    type BwdEdges = AsyncEdges<'a>;
+
+    fn states(&self) -> Self::States {
+        return IdStateRange::new(1 << self.network.num_vars());
+    }
+
+    fn fwd(&self) -> Self::FwdEdges {
+        return AsyncEdges {
+            network: self.network,
+            forward: true,
+        };
+    }
+
+    fn bwd(&self) -> Self::BwdEdges {
+        return AsyncEdges {
+            network: self.network,
+            forward: false,
+        };
+    }
+}
+
+impl<'a> EvolutionOperator for AsyncEdges<'a> {
+    type State = IdState;
+    type Iterator = AsyncEdgeIterator<'a>;
+
+    fn step(&self, current: Self::State) -> Self::Iterator {
+        return AsyncEdgeIterator {
+            edges: *self,
+            state: current,
+            next: 0,
+        };
+    }
+}
+
+impl Iterator for AsyncEdgeIterator<'_> {
+    type Item = IdState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let network = self.edges.network;
+        while self.next < network.num_vars() {
+            let variable = self.next;
+            self.next += 1;
+            // Forward: the variable is updated in the current state. Backward: the variable
+            // is updated in the predecessor, i.e. the state with the variable flipped.
+            let source = if self.edges.forward {
+                self.state
+            } else {
+                self.state.flip_bit(variable)
+            };
+            if network.is_enabled(variable, source) {
+                return Some(self.state.flip_bit(variable));
+            }
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{EvolutionOperator, Graph};
+    use crate::logic::BoolExpr;
+    use crate::networks::test_util::random_network;
+    use crate::networks::BooleanNetwork;
+    use crate::testing::Random;
+    use crate::IdState;
+    use std::collections::HashSet;
+
+    #[test]
+    fn async_graph_edges() {
+        // x0 = x1, x1 = !x0 (a negative cycle).
+        let network = BooleanNetwork::new(
+            vec!["x0".to_string(), "x1".to_string()],
+            vec![
+                BoolExpr::Atom(1),
+                BoolExpr::Not(Box::new(BoolExpr::Atom(0))),
+            ],
+        );
+        let graph = network.async_graph();
+        let fwd = graph.fwd();
+        let successors = |s: usize| fwd.step(IdState::from(s)).collect::<Vec<_>>();
+        assert_eq!(vec![IdState::from(0b10)], successors(0b00));
+        assert_eq!(vec![IdState::from(0b11)], successors(0b10));
+        assert_eq!(vec![IdState::from(0b01)], successors(0b11));
+        assert_eq!(vec![IdState::from(0b00)], successors(0b01));
+        let bwd = graph.bwd();
+        let predecessors: Vec<IdState> = bwd.step(IdState::from(0b00)).collect();
+        assert_eq!(vec![IdState::from(0b01)], predecessors);
+    }
+
+    #[test]
+    fn async_graph_fwd_bwd_consistent() {
+        let mut random = Random::new(21);
+        for _ in 0..10 {
+            let network = random_network(&mut random, 5);
+            let graph = network.async_graph();
+            let mut fwd_edges = HashSet::new();
+            let mut bwd_edges = HashSet::new();
+            for state in graph.states() {
+                for successor in graph.fwd().step(state) {
+                    assert_ne!(state, successor);
+                    fwd_edges.insert((state, successor));
+                }
+                for predecessor in graph.bwd().step(state) {
+                    bwd_edges.insert((predecessor, state));
+                }
+                let fixed_point = graph.fwd().step(state).next().is_none();
+                assert_eq!(network.is_fixed_point(state), fixed_point);
+            }
+            assert_eq!(fwd_edges, bwd_edges);
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use super::BooleanNetwork;
+use crate::logic::{BoolExpr, BoolOp, ParseError};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// **(internal)** A rule `target, function` read from a `.bnet` file, before the atoms of
+/// the function are resolved.
+struct Rule {
+    line: usize,
+    target: String,
+    function: BoolExpr<String>,
+    /// The text of the function and its (zero-based) column in the line.
+    text: String,
+    column: usize,
+}
+
+impl BooleanNetwork {
+    /// Read a network in the BoolNet `.bnet` format: an optional `targets, factors` header,
+    /// followed by one `target, function` line per variable. Variables are ordered as their
+    /// rules in the file. Everything after `#` is a comment.
+    ///
+    /// Functions use the syntax of `BoolExpr` parsing (typically only `!`, `&`, `|`,
+    /// parentheses and constants `0`/`1`). Errors report the line and column of the syntax
+    /// error, of the duplicate target, or of the first use of an undeclared variable.
+    pub fn from_bnet(input: &str) -> Result<BooleanNetwork, ParseError> {
+        let mut rules: Vec<Rule> = Vec::new();
+        let mut targets: HashMap<String, usize> = HashMap::new();
+        let mut header_allowed = true;
+        for (line_index, line) in input.lines().enumerate() {
+            let line_number = line_index + 1;
+            let line = line.split('#').next().unwrap();
+            if line.trim().is_empty() {
+                continue;
+            }
+            let error = |column: usize, expected: &str, found: String| ParseError {
+                line: line_number,
+                column: column + 1,
+                expected: expected.to_string(),
+                found,
+            };
+            let (target, text) = match line.find(',') {
+                Some(comma) => (&line[..comma], &line[(comma + 1)..]),
+                None => {
+                    let column = line.chars().count();
+                    return Err(error(column, "`,`", "end of line".to_string()));
+                }
+            };
+            if header_allowed && is_header(target, text) {
+                header_allowed = false;
+                continue;
+            }
+            header_allowed = false;
+            let target_column = column_of(line, target.len() - target.trim_start().len());
+            let target = target.trim();
+            if !is_identifier(target) {
+                let found = format!("`{}`", target);
+                return Err(error(target_column, "a variable name", found));
+            }
+            if let Some(previous) = targets.get(target) {
+                let found = format!("`{}` (already defined on line {})", target, previous);
+                return Err(error(target_column, "a new target", found));
+            }
+            let column = column_of(line, line.len() - text.len());
+            let function = text.parse::<BoolExpr<String>>().map_err(|e| ParseError {
+                line: line_number,
+                column: column + e.column,
+                expected: e.expected,
+                found: e.found,
+            })?;
+            targets.insert(target.to_string(), line_number);
+            rules.push(Rule {
+                line: line_number,
+                target: target.to_string(),
+                function,
+                text: text.to_string(),
+                column,
+            });
+        }
+        let index: HashMap<&str, usize> = rules
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| (rule.target.as_str(), i))
+            .collect();
+        let mut functions = Vec::new();
+        for rule in &rules {
+            if let Some(atom) = rule
+                .function
+                .atoms()
+                .into_iter()
+                .find(|atom| !index.contains_key(atom.as_str()))
+            {
+                let offset = find_identifier(&rule.text, &atom).unwrap_or(0);
+                return Err(ParseError {
+                    line: rule.line,
+                    column: rule.column + offset + 1,
+                    expected: "a declared variable".to_string(),
+                    found: format!("`{}`", atom),
+                });
+            }
+            functions.push(rule.function.map_atoms(|atom| index[atom.as_str()]));
+        }
+        let names = rules.into_iter().map(|rule| rule.target).collect();
+        return Ok(BooleanNetwork::new(names, functions));
+    }
+
+    /// Write this network in the BoolNet `.bnet` format (see `from_bnet`).
+    ///
+    /// Since the format does not support `^`, `=>` and `<=>`, these operators are rewritten
+    /// using `!`, `&` and `|`. Functions which contain constants are simplified (see
+    /// `BoolExpr::simplify`), so that a constant can only be the whole function.
+    ///
+    /// Panics if a variable name is not an identifier (`[a-zA-Z_][a-zA-Z0-9_]*`, other than
+    /// `true` and `false`), since such a network could not be read back.
+    pub fn to_bnet(&self) -> String {
+        for name in self.names() {
+            if !is_identifier(name) {
+                panic!(
+                    "Variable name `{}` cannot be written in the .bnet format.",
+                    name
+                );
+            }
+        }
+        let mut result = String::from("targets, factors\n");
+        for variable in 0..self.num_vars() {
+            let mut function = basic_operators(self.update_function(variable));
+            if has_constants(&function) {
+                function = function.simplify();
+            }
+            let text = match function {
+                BoolExpr::Const(value) => (value as u8).to_string(),
+                _ => function
+                    .map_atoms(|v| self.name(*v).to_string())
+                    .to_string(),
+            };
+            writeln!(result, "{}, {}", self.name(variable), text).unwrap();
+        }
+        return result;
+    }
+}
+
+/// **(internal)** True if the line is the `targets, factors` header.
+fn is_header(target: &str, text: &str) -> bool {
+    return target.trim().eq_ignore_ascii_case("targets")
+        && text.trim().eq_ignore_ascii_case("factors");
+}
+
+/// **(internal)** True if the name can be used as an atom of a `BoolExpr` in the text format.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let valid_start = match chars.next() {
+        Some(c) => c.is_ascii_alphabetic() || c == '_',
+        None => false,
+    };
+    return valid_start
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name != "true"
+        && name != "false";
+}
+
+/// **(internal)** Zero-based column of the given byte offset in the line.
+fn column_of(line: &str, offset: usize) -> usize {
+    return line[..offset].chars().count();
+}
+
+/// **(internal)** Zero-based column of the first occurrence of the identifier `name`
+/// in the `text` (as a whole identifier, not as a part of a longer one).
+fn find_identifier(text: &str, name: &str) -> Option<usize> {
+    let is_part = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut start = None;
+    for (i, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (start, is_part(c)) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                if &text[s..i] == name {
+                    return Some(column_of(text, s));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    return None;
+}
+
+/// **(internal)** True if the expression contains a constant (including empty `And`/`Or`).
+fn has_constants(expr: &BoolExpr<usize>) -> bool {
+    return expr.fold(|node, children: Vec<bool>| match node {
+        BoolExpr::Const(_) => true,
+        BoolExpr::And(args) | BoolExpr::Or(args) if args.is_empty() => true,
+        _ => children.into_iter().any(|it| it),
+    });
+}
+
+/// **(internal)** Rewrite `Xor`, `Iff` and `Imp` using negation, conjunction and disjunction.
+fn basic_operators(expr: &BoolExpr<usize>) -> BoolExpr<usize> {
+    let not = |e: &BoolExpr<usize>| BoolExpr::Not(Box::new(e.clone()));
+    let op = |op: BoolOp, left: BoolExpr<usize>, right: BoolExpr<usize>| BoolExpr::Op {
+        op,
+        left: Box::new(left),
+        right: Box::new(right),
+    };
+    return expr.fold(|node, mut children| match node {
+        BoolExpr::Const(_) | BoolExpr::Atom(_) => node.clone(),
+        BoolExpr::Not(_) => BoolExpr::Not(Box::new(children.pop().unwrap())),
+        BoolExpr::And(_) => BoolExpr::And(children),
+        BoolExpr::Or(_) => BoolExpr::Or(children),
+        BoolExpr::Op { op: kind, .. } => {
+            let right = children.pop().unwrap();
+            let left = children.pop().unwrap();
+            match kind {
+                BoolOp::And | BoolOp::Or => op(*kind, left, right),
+                BoolOp::Imp => op(BoolOp::Or, not(&left), right),
+                BoolOp::Xor => op(
+                    BoolOp::Or,
+                    op(BoolOp::And, left.clone(), not(&right)),
+                    op(BoolOp::And, not(&left), right),
+                ),
+                BoolOp::Iff => op(
+                    BoolOp::Or,
+                    op(BoolOp::And, left.clone(), right.clone()),
+                    op(BoolOp::And, not(&left), not(&right)),
+                ),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::{BoolExpr, ParseError};
+    use crate::networks::BooleanNetwork;
+    use crate::testing::{ExprGenerator, Random};
+
+    fn bnet_error(input: &str) -> ParseError {
+        return BooleanNetwork::from_bnet(input).unwrap_err();
+    }
+
+    #[test]
+    fn bnet_read() {
+        let input = "# A model.\ntargets, factors\nCycB, !Cdh1 # inhibition\n\n  Cdh1 ,  !CycB | p27\np27, 1\n";
+        let network = BooleanNetwork::from_bnet(input).unwrap();
+        assert_eq!(vec!["CycB", "Cdh1", "p27"], network.names());
+        assert_eq!(
+            "!1",
+            network
+                .update_function(0)
+                .map_atoms(|v| v.to_string())
+                .to_string()
+        );
+        assert_eq!(&BoolExpr::Const(true), network.update_function(2));
+        // The header is optional.
+        let network = BooleanNetwork::from_bnet("a, a & b\nb, !a").unwrap();
+        assert_eq!(2, network.num_vars());
+    }
+
+    #[test]
+    fn bnet_read_large() {
+        let rules: Vec<String> = (0..100)
+            .map(|i| format!("v{}, !v{}", i, (i + 1) % 100))
+            .collect();
+        let network = BooleanNetwork::from_bnet(&rules.join("\n")).unwrap();
+        assert_eq!(100, network.num_vars());
+        assert_eq!(Some(99), network.find_variable("v99"));
+        assert_eq!(
+            BooleanNetwork::from_bnet(&network.to_bnet()).unwrap(),
+            network
+        );
+    }
+
+    #[test]
+    fn bnet_write() {
+        let input = "targets, factors\nCycB, !Cdh1\nCdh1, !CycB | p27\np27, 1\n";
+        let network = BooleanNetwork::from_bnet(input).unwrap();
+        assert_eq!(input, network.to_bnet());
+        let names = vec!["a".to_string(), "b".to_string()];
+        let xor: BoolExpr<String> = "a ^ b & false".parse().unwrap();
+        let iff: BoolExpr<String> = "(a => b) <=> b".parse().unwrap();
+        let functions = vec![xor, iff]
+            .into_iter()
+            .map(|f| f.map_atoms(|atom| names.iter().position(|n| n == atom).unwrap()))
+            .collect();
+        let network = BooleanNetwork::new(names, functions);
+        assert_eq!(
+            "targets, factors\na, a\nb, (!a | b) & b | !(!a | b) & !b\n",
+            network.to_bnet()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Variable name `a-b` cannot be written in the .bnet format.")]
+    fn bnet_write_invalid_name() {
+        let names = vec!["a-b".to_string()];
+        BooleanNetwork::new(names, vec![BoolExpr::Atom(0)]).to_bnet();
+    }
+
+    #[test]
+    fn bnet_round_trip() {
+        let mut random = Random::new(25);
+        let generator = ExprGenerator::new(4, 4).with_constant_probability(0.05);
+        for _ in 0..20 {
+            let names: Vec<String> = (0..4).map(|i| format!("v_{}", i)).collect();
+            let functions = (0..4).map(|_| generator.generate(&mut random)).collect();
+            let network = BooleanNetwork::new(names, functions);
+            let read = BooleanNetwork::from_bnet(&network.to_bnet()).unwrap();
+            assert_eq!(network.names(), read.names());
+            for v in 0..4 {
+                let (expected, actual) = (network.update_function(v), read.update_function(v));
+                assert!(expected.is_equivalent(actual), "{} {}", expected, actual);
+            }
+        }
+    }
+
+    #[test]
+    fn bnet_errors() {
+        let error = bnet_error("a, b\nb, a & c\n");
+        assert_eq!((2, 8, "`c`"), (error.line, error.column, &*error.found));
+        assert_eq!("a declared variable", error.expected);
+        let error = bnet_error("targets, factors\na, 1\n a, 0\n");
+        assert_eq!((3, 2), (error.line, error.column));
+        assert_eq!("`a` (already defined on line 2)", error.found);
+        let error = bnet_error("a, a &\n");
+        assert_eq!((1, 7), (error.line, error.column));
+        assert_eq!("end of input", error.found);
+        let error = bnet_error("a, (a | !b\nb, 0");
+        assert_eq!((1, 11, "`)`"), (error.line, error.column, &*error.expected));
+        let error = bnet_error("# header\na a\n");
+        assert_eq!((2, 4, "`,`"), (error.line, error.column, &*error.expected));
+        let error = bnet_error("a-b, 1\n");
+        assert_eq!((1, 1, "`a-b`"), (error.line, error.column, &*error.found));
+        // The header is only recognized on the first line.
+        let error = bnet_error("targets, factors\ntargets, factors\n");
+        assert_eq!(
+            (2, 10, "`factors`"),
+            (error.line, error.column, &*error.found)
+        );
+    }
+}
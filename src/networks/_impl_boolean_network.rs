@@ -0,0 +1,171 @@
+use super::{AsyncGraph, BooleanNetwork, UpdateGraph, UpdateScheme};
+use crate::logic::BoolExpr;
+use crate::IdState;
+use std::collections::HashSet;
+
+impl BooleanNetwork {
+    /// Create a network where `functions[i]` is the update function of the variable
+    /// `names[i]`.
+    ///
+    /// Panics if the numbers of names and functions differ, if the names are not unique,
+    /// or if a function refers to a variable which does not exist.
+    ///
+    /// The network itself can have any number of variables, but its state transition graphs
+    /// (and all methods working with an `IdState`) need fewer variables than the number of
+    /// bits in an `IdState`.
+    pub fn new(names: Vec<String>, functions: Vec<BoolExpr<usize>>) -> BooleanNetwork {
+        if names.len() != functions.len() {
+            panic!(
+                "Network has {} variables, but {} update functions.",
+                names.len(),
+                functions.len()
+            );
+        }
+        let mut seen = HashSet::new();
+        for name in &names {
+            if !seen.insert(name) {
+                panic!("Duplicate variable name `{}`.", name);
+            }
+        }
+        for (variable, function) in functions.iter().enumerate() {
+            if let Some(atom) = function.atoms().into_iter().find(|a| *a >= names.len()) {
+                panic!(
+                    "Update function of `{}` uses unknown variable {}.",
+                    names[variable], atom
+                );
+            }
+        }
+        return BooleanNetwork { names, functions };
+    }
+
+    /// The number of variables of this network.
+    pub fn num_vars(&self) -> usize {
+        return self.names.len();
+    }
+
+    /// The names of all variables (indexed by variables).
+    pub fn names(&self) -> &[String] {
+        return &self.names;
+    }
+
+    /// The name of the given `variable`.
+    pub fn name(&self, variable: usize) -> &str {
+        return &self.names[variable];
+    }
+
+    /// The index of the variable with the given name (if it exists).
+    pub fn find_variable(&self, name: &str) -> Option<usize> {
+        return self.names.iter().position(|it| it == name);
+    }
+
+    /// The update function of the given `variable`.
+    pub fn update_function(&self, variable: usize) -> &BoolExpr<usize> {
+        return &self.functions[variable];
+    }
+
+    /// The value of the update function of `variable` in the given `state`.
+    pub fn next_value(&self, variable: usize, state: IdState) -> bool {
+        return self.functions[variable].eval_id_state(state);
+    }
+
+    /// True if no variable can change its value in the given `state`.
+    pub fn is_fixed_point(&self, state: IdState) -> bool {
+        return (0..self.num_vars()).all(|v| !self.is_enabled(v, state));
+    }
+
+    /// The asynchronous state transition graph of this network.
+    ///
+    /// Panics if the states of the network cannot be represented by `IdState`s.
+    pub fn async_graph(&self) -> AsyncGraph<'_> {
+        check_state_size(self.num_vars());
+        return AsyncGraph { network: self };
+    }
+
+    /// The state transition graph of this network under the given update `scheme`.
+    ///
+    /// Panics if the states of the network cannot be represented by `IdState`s, or if the
+    /// priority classes of `UpdateScheme::Priority` are not a partition of the network
+    /// variables.
+    pub fn update_graph(&self, scheme: UpdateScheme) -> UpdateGraph<'_> {
+        check_state_size(self.num_vars());
+        if let UpdateScheme::Priority(classes) = &scheme {
+            let mut variables: Vec<usize> = classes.iter().flatten().cloned().collect();
+            variables.sort_unstable();
+            if variables != (0..self.num_vars()).collect::<Vec<_>>() {
+                panic!(
+                    "Priority classes {:?} are not a partition of variables.",
+                    classes
+                );
+            }
+        }
+        return UpdateGraph {
+            network: self,
+            scheme,
+        };
+    }
+
+    /// True if the update function of `variable` disagrees with its value in `state`.
+    pub fn is_enabled(&self, variable: usize, state: IdState) -> bool {
+        return self.next_value(variable, state) != state.get_bit(variable);
+    }
+}
+
+/// **(internal)** Panic if states of a network with `num_vars` variables do not fit into
+/// an `IdState`.
+pub(super) fn check_state_size(num_vars: usize) {
+    if num_vars >= std::mem::size_of::<usize>() * 8 {
+        panic!(
+            "Network with {} variables is too large for an IdState graph.",
+            num_vars
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logic::BoolExpr;
+    use crate::networks::test_util::network;
+    use crate::networks::BooleanNetwork;
+    use crate::IdState;
+
+    #[test]
+    fn network_basic() {
+        let network = network(&["x1 & !x2", "x0", "x2"]);
+        assert_eq!(3, network.num_vars());
+        assert_eq!("x1", network.name(1));
+        assert_eq!(None, network.find_variable("x3"));
+        assert_eq!(&BoolExpr::Atom(0), network.update_function(1));
+        assert!(network.next_value(0, IdState::from(0b010)));
+        assert!(network.is_fixed_point(IdState::from(0b000)));
+        assert!(network.is_fixed_point(IdState::from(0b011)));
+        assert!(!network.is_fixed_point(IdState::from(0b010)));
+        let fixed_points = (0..8)
+            .map(IdState::from)
+            .filter(|s| network.is_fixed_point(*s));
+        assert_eq!(3, fixed_points.count());
+    }
+
+    #[test]
+    #[should_panic]
+    fn network_unknown_variable() {
+        network(&["x1", "x2"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn network_duplicate_names() {
+        let names = vec!["a".to_string(), "a".to_string()];
+        BooleanNetwork::new(names, vec![BoolExpr::Atom(0), BoolExpr::Atom(1)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Network with 64 variables is too large for an IdState graph.")]
+    fn network_large() {
+        // Large networks can be created, only their `IdState` graphs are not supported.
+        let functions: Vec<String> = (0..64).map(|i| format!("!x{}", (i + 1) % 64)).collect();
+        let functions: Vec<&str> = functions.iter().map(|it| it.as_str()).collect();
+        let network = network(&functions);
+        assert_eq!(64, network.num_vars());
+        network.async_graph();
+    }
+}
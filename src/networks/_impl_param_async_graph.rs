@@ -0,0 +1,132 @@
+use super::{BddParams, ParamAsyncEdges, ParamAsyncGraph};
+use crate::collections::sets::Set;
+use crate::param_graph::{EvolutionOperator, Graph};
+use crate::{IdState, IdStateRange};
+
+impl<'a> Graph for ParamAsyncGraph<'a> {
+    type State = IdState;
+    type Params = BddParams;
+    type States = IdStateRange;
+    type FwdEdges = ParamAsyncEdges<'a>;
+    type BwdEdges = ParamAsyncEdges<'a>;
+
+    fn states(&self) -> Self::States {
+        return IdStateRange::new(1 << self.network.num_vars());
+    }
+
+    fn fwd(&self) -> Self::FwdEdges {
+        return ParamAsyncEdges {
+            network: self.network,
+            forward: true,
+        };
+    }
+
+    fn bwd(&self) -> Self::BwdEdges {
+        return ParamAsyncEdges {
+            network: self.network,
+            forward: false,
+        };
+    }
+}
+
+impl EvolutionOperator for ParamAsyncEdges<'_> {
+    type State = IdState;
+    type Params = BddParams;
+    type Iterator = std::vec::IntoIter<(IdState, BddParams)>;
+
+    /// Successors (or predecessors) of the `current` state which differ in one variable,
+    /// together with the non-empty sets of parameter valuations which enable the transition.
+    fn step(&self, current: Self::State) -> Self::Iterator {
+        let network = self.network;
+        let mut result = Vec::new();
+        for variable in 0..network.num_vars() {
+            let target = current.flip_bit(variable);
+            // Backward, the variable is updated in the predecessor.
+            let source = if self.forward { current } else { target };
+            let params = network.enabled_params(variable, source);
+            if !params.is_empty() {
+                result.push((target, params));
+            }
+        }
+        return result.into_iter();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::bitvectors::ArrayBitVector;
+    use crate::collections::sets::{ElementSet, IterableSet};
+    use crate::graph::{EvolutionOperator as _, Graph as _};
+    use crate::logic::BoolExpr;
+    use crate::networks::test_util::random_parametrised_network;
+    use crate::networks::{ParamAtom, ParametrisedNetwork};
+    use crate::param_graph::{EvolutionOperator, Graph};
+    use crate::testing::Random;
+    use crate::IdState;
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn param_async_graph_instances() {
+        // Every instantiation of the parameters gives the corresponding non-parametrised graph.
+        let mut random = Random::new(23);
+        for _ in 0..3 {
+            let network = random_parametrised_network(&mut random);
+            let graph = network.async_graph();
+            for valuation in network.unit_params().iter() {
+                let instance = network.instantiate::<ArrayBitVector>(&valuation);
+                let instance_graph = instance.async_graph();
+                for state in graph.states() {
+                    let expected: Vec<IdState> = instance_graph.fwd().step(state).collect();
+                    let actual: Vec<IdState> = graph
+                        .fwd()
+                        .step(state)
+                        .filter(|(_, params)| params.contains(&valuation))
+                        .map(|(target, _)| target)
+                        .collect();
+                    assert_eq!(expected, actual);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn param_async_graph_fwd_bwd_consistent() {
+        let mut random = Random::new(2023);
+        for _ in 0..5 {
+            let network = random_parametrised_network(&mut random);
+            let graph = network.async_graph();
+            let mut fwd_edges = HashMap::new();
+            let mut bwd_edges = HashMap::new();
+            for state in graph.states() {
+                for (target, params) in graph.fwd().step(state) {
+                    fwd_edges.insert((state, target), params);
+                }
+                for (source, params) in graph.bwd().step(state) {
+                    bwd_edges.insert((source, state), params);
+                }
+            }
+            assert_eq!(fwd_edges, bwd_edges);
+            let edges: HashSet<(IdState, IdState)> = fwd_edges.keys().cloned().collect();
+            assert!(edges.iter().all(|(s, t)| s != t));
+        }
+    }
+
+    #[test]
+    fn param_async_graph_constant_parameter() {
+        // x0 = p: the only edge exists for exactly one valuation of the parameter.
+        let network = ParametrisedNetwork::new(
+            vec!["x0".to_string()],
+            vec![("p".to_string(), 0)],
+            vec![BoolExpr::Atom(ParamAtom::Parameter {
+                parameter: 0,
+                args: vec![],
+            })],
+        );
+        let graph = network.async_graph();
+        let edges: Vec<_> = graph.fwd().step(IdState::from(0)).collect();
+        assert_eq!(1, edges.len());
+        assert_eq!(Some(1), edges[0].1.count().to_u64());
+        let back: Vec<_> = graph.bwd().step(IdState::from(0)).collect();
+        assert_eq!(IdState::from(1), back[0].0);
+    }
+}
@@ -0,0 +1,317 @@
+use super::{BddParams, BooleanNetwork, ParamAsyncGraph, ParamAtom, ParametrisedNetwork};
+use crate::collections::bdd::BddManager;
+use crate::collections::bitvectors::{ArrayBitVector, BitVector};
+use crate::collections::sets::Set;
+use crate::logic::BoolExpr;
+use crate::IdState;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+/// **(internal)** Maximal total number of BDD variables of all parameters (i.e. rows of their
+/// function tables). For example, a single parameter can have an arity of at most 16.
+const MAX_PARAMETER_VARS: usize = 1 << 16;
+
+impl ParametrisedNetwork {
+    /// Create a network where `functions[i]` is the update function of the variable
+    /// `names[i]` and `parameters` gives the names and arities of the uninterpreted functions.
+    ///
+    /// Panics if the numbers of names and functions differ, if the names of variables or
+    /// parameters are not unique, if a function refers to a variable or parameter which
+    /// does not exist, if a parameter is applied to a wrong number of arguments, or if the
+    /// parameters together have more than `2^16` function table entries (each entry is one
+    /// BDD variable, so e.g. an arity of 17 or more is rejected). Like in
+    /// `BooleanNetwork::new`, the number of variables is only limited by the methods which
+    /// work with `IdState`s.
+    pub fn new(
+        names: Vec<String>,
+        parameters: Vec<(String, usize)>,
+        functions: Vec<BoolExpr<ParamAtom>>,
+    ) -> ParametrisedNetwork {
+        if names.len() != functions.len() {
+            panic!(
+                "Network has {} variables, but {} update functions.",
+                names.len(),
+                functions.len()
+            );
+        }
+        let mut seen = HashSet::new();
+        for name in names.iter().chain(parameters.iter().map(|(name, _)| name)) {
+            if !seen.insert(name) {
+                panic!("Duplicate variable or parameter name `{}`.", name);
+            }
+        }
+        for (variable, function) in functions.iter().enumerate() {
+            for atom in function.atoms() {
+                let valid = match &atom {
+                    ParamAtom::Variable(v) => *v < names.len(),
+                    ParamAtom::Parameter { parameter, args } => {
+                        *parameter < parameters.len()
+                            && parameters[*parameter].1 == args.len()
+                            && args.iter().all(|v| *v < names.len())
+                    }
+                };
+                if !valid {
+                    panic!(
+                        "Update function of `{}` contains invalid atom {:?}.",
+                        names[variable], atom
+                    );
+                }
+            }
+        }
+        let mut offsets = Vec::new();
+        let mut num_parameter_vars = 0;
+        for (name, arity) in &parameters {
+            offsets.push(num_parameter_vars);
+            // A parameter of arity `k` is represented by `2^k` BDD variables.
+            let total = u32::try_from(*arity)
+                .ok()
+                .and_then(|arity| 1usize.checked_shl(arity))
+                .and_then(|entries| entries.checked_add(num_parameter_vars))
+                .filter(|total| *total <= MAX_PARAMETER_VARS);
+            match total {
+                Some(total) => num_parameter_vars = total,
+                None => panic!("Parameter `{}` with arity {} is too large.", name, arity),
+            }
+        }
+        return ParametrisedNetwork {
+            names,
+            parameters,
+            functions,
+            offsets,
+            manager: BddManager::new_shared(num_parameter_vars),
+        };
+    }
+
+    /// Create a network without parameters with the same update functions as `network`.
+    pub fn from_network(network: &BooleanNetwork) -> ParametrisedNetwork {
+        let functions = (0..network.num_vars())
+            .map(|v| {
+                network
+                    .update_function(v)
+                    .map_atoms(|a| ParamAtom::Variable(*a))
+            })
+            .collect();
+        return ParametrisedNetwork::new(network.names().to_vec(), Vec::new(), functions);
+    }
+
+    /// The number of variables of this network.
+    pub fn num_vars(&self) -> usize {
+        return self.names.len();
+    }
+
+    /// The names of all variables (indexed by variables).
+    pub fn names(&self) -> &[String] {
+        return &self.names;
+    }
+
+    /// The index of the variable with the given name (if it exists).
+    pub fn find_variable(&self, name: &str) -> Option<usize> {
+        return self.names.iter().position(|it| it == name);
+    }
+
+    /// The names and arities of all parameters (indexed by parameters).
+    pub fn parameters(&self) -> &[(String, usize)] {
+        return &self.parameters;
+    }
+
+    /// The index of the parameter with the given name (if it exists).
+    pub fn find_parameter(&self, name: &str) -> Option<usize> {
+        return self.parameters.iter().position(|(it, _)| it == name);
+    }
+
+    /// The update function of the given `variable`.
+    pub fn update_function(&self, variable: usize) -> &BoolExpr<ParamAtom> {
+        return &self.functions[variable];
+    }
+
+    /// The number of BDD variables used to encode parameter valuations (the total size of
+    /// the truth tables of all parameters).
+    pub fn num_parameter_vars(&self) -> usize {
+        return self.manager.borrow().num_vars();
+    }
+
+    /// The BDD variable which encodes the value of `parameter` for the given `row` of its
+    /// truth table (the `i`-th bit of `row` is the value of the `i`-th argument).
+    pub fn parameter_var(&self, parameter: usize, row: usize) -> usize {
+        return self.offsets[parameter] + row;
+    }
+
+    /// The manager of all BDDs representing parameter sets of this network.
+    pub fn manager(&self) -> &Rc<RefCell<BddManager>> {
+        return &self.manager;
+    }
+
+    /// The set of all parameter valuations.
+    pub fn unit_params(&self) -> BddParams {
+        return BddParams::universe(&self.manager);
+    }
+
+    /// The set of parameter valuations for which the update function of `variable` is true
+    /// in the given `state`.
+    pub fn update_params(&self, variable: usize, state: IdState) -> BddParams {
+        let expr = self.functions[variable].replace_atoms(|atom| match atom {
+            ParamAtom::Variable(v) => BoolExpr::Const(state.get_bit(*v)),
+            ParamAtom::Parameter { parameter, args } => {
+                BoolExpr::Atom(self.parameter_var(*parameter, row(args, state)))
+            }
+        });
+        return BddParams::from_expr(&self.manager, &expr);
+    }
+
+    /// The set of parameter valuations for which `variable` can change its value in the
+    /// given `state`.
+    pub fn enabled_params(&self, variable: usize, state: IdState) -> BddParams {
+        let update = self.update_params(variable, state);
+        return if state.get_bit(variable) {
+            self.unit_params().minus(&update)
+        } else {
+            update
+        };
+    }
+
+    /// The `BooleanNetwork` obtained by replacing every parameter by the function given by
+    /// the `valuation` (a vector of length `num_parameter_vars`). Parameters are replaced by
+    /// their canonical DNFs (see `BoolExpr::from_truth_table`).
+    pub fn instantiate<BV: BitVector>(&self, valuation: &BV) -> BooleanNetwork {
+        let functions = self.functions.iter().map(|function| {
+            function.replace_atoms(|atom| match atom {
+                ParamAtom::Variable(v) => BoolExpr::Atom(*v),
+                ParamAtom::Parameter { parameter, args } => {
+                    let table: Vec<bool> = (0..(1 << args.len()))
+                        .map(|r| valuation.get(self.parameter_var(*parameter, r)))
+                        .collect();
+                    BoolExpr::from_truth_table(&ArrayBitVector::from(table), args)
+                }
+            })
+        });
+        return BooleanNetwork::new(self.names.clone(), functions.collect());
+    }
+
+    /// The asynchronous state transition graph of this network.
+    ///
+    /// Panics if the states of the network cannot be represented by `IdState`s.
+    pub fn async_graph(&self) -> ParamAsyncGraph<'_> {
+        super::_impl_boolean_network::check_state_size(self.names.len());
+        return ParamAsyncGraph { network: self };
+    }
+}
+
+/// **(internal)** The row of a parameter truth table given by the values of `args` in `state`.
+fn row(args: &[usize], state: IdState) -> usize {
+    return args
+        .iter()
+        .enumerate()
+        .fold(0, |row, (i, v)| row | (state.get_bit(*v) as usize) << i);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::bitvectors::{ArrayBitVector, BitVector};
+    use crate::collections::sets::{ElementSet, Set};
+    use crate::logic::BoolExpr;
+    use crate::networks::{BooleanNetwork, ParamAtom, ParametrisedNetwork};
+    use crate::IdState;
+
+    fn parameter(parameter: usize, args: Vec<usize>) -> BoolExpr<ParamAtom> {
+        return BoolExpr::Atom(ParamAtom::Parameter { parameter, args });
+    }
+
+    #[test]
+    fn parametrised_network_params() {
+        // x0 = f(x1, x2) & !x2, x1 = g, x2 = x0
+        let network = ParametrisedNetwork::new(
+            vec!["x0".to_string(), "x1".to_string(), "x2".to_string()],
+            vec![("f".to_string(), 2), ("g".to_string(), 0)],
+            vec![
+                BoolExpr::And(vec![
+                    parameter(0, vec![1, 2]),
+                    BoolExpr::Not(Box::new(BoolExpr::Atom(ParamAtom::Variable(2)))),
+                ]),
+                parameter(1, vec![]),
+                BoolExpr::Atom(ParamAtom::Variable(0)),
+            ],
+        );
+        assert_eq!(5, network.num_parameter_vars());
+        assert_eq!(Some(1), network.find_parameter("g"));
+        assert_eq!(4, network.parameter_var(1, 0));
+        // In state `x1 = 1`, `x0` is updated to `f(1, 0)`, i.e. BDD variable 1.
+        let state = IdState::from(0b010);
+        let update = network.update_params(0, state);
+        assert_eq!(Some(16), update.count().to_u64());
+        assert!(update.contains(&ArrayBitVector::from_ones(5, vec![1])));
+        assert_eq!(update, network.enabled_params(0, state));
+        // `x1` is enabled when `g` is false.
+        let enabled = network.enabled_params(1, state);
+        assert!(enabled.contains(&ArrayBitVector::from_ones(5, vec![0, 1, 2, 3])));
+        assert!(!enabled.contains(&ArrayBitVector::from_ones(5, vec![4])));
+        // `x2` blocks `x0` for all parameters.
+        assert!(network.update_params(0, IdState::from(0b110)).is_empty());
+        let instance = network.instantiate(&ArrayBitVector::from_ones(5, vec![3, 4]));
+        // `f` is a conjunction, `g` is true.
+        assert!(instance.is_fixed_point(IdState::from(0b010)));
+        assert!(!instance.next_value(0, IdState::from(0b011)));
+        assert!(instance.next_value(1, IdState::from(0b000)));
+    }
+
+    #[test]
+    fn parametrised_network_without_parameters() {
+        let network = BooleanNetwork::new(
+            vec!["a".to_string(), "b".to_string()],
+            vec![
+                BoolExpr::Atom(1),
+                BoolExpr::Not(Box::new(BoolExpr::Atom(0))),
+            ],
+        );
+        let parametrised = ParametrisedNetwork::from_network(&network);
+        assert_eq!(0, parametrised.num_parameter_vars());
+        assert_eq!(network, parametrised.instantiate(&ArrayBitVector::empty(0)));
+        assert_eq!(
+            parametrised.unit_params(),
+            parametrised.enabled_params(0, IdState::from(0b10))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn parametrised_network_wrong_arity() {
+        ParametrisedNetwork::new(
+            vec!["a".to_string()],
+            vec![("f".to_string(), 2)],
+            vec![parameter(0, vec![0])],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Parameter `f` with arity 64 is too large.")]
+    fn parametrised_network_huge_arity() {
+        ParametrisedNetwork::new(
+            vec!["a".to_string()],
+            vec![("f".to_string(), 64)],
+            vec![BoolExpr::Atom(ParamAtom::Variable(0))],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Parameter `g` with arity 40 is too large.")]
+    fn parametrised_network_large_arity() {
+        ParametrisedNetwork::new(
+            vec!["a".to_string()],
+            vec![("f".to_string(), 2), ("g".to_string(), 40)],
+            vec![BoolExpr::Atom(ParamAtom::Variable(0))],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Parameter `c` with arity 15 is too large.")]
+    fn parametrised_network_too_many_parameter_vars() {
+        // Each parameter alone fits, but together they have too many function table entries.
+        let parameters = vec!["a", "b", "c"].into_iter().map(|p| (p.to_string(), 15));
+        ParametrisedNetwork::new(
+            vec!["x".to_string()],
+            parameters.collect(),
+            vec![BoolExpr::Atom(ParamAtom::Variable(0))],
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use super::{BooleanNetwork, Regulation, RegulationError, RegulatoryGraph};
+use crate::collections::graphs::{EdgeLabels, IdVertex, VertexLabels};
+use crate::logic::Monotonicity;
+use std::collections::HashMap;
+use std::fmt::{Display, Error, Formatter};
+
+impl Regulation {
+    /// Create a regulation of `target` by `regulator`.
+    pub fn new(
+        regulator: usize,
+        target: usize,
+        monotonicity: Option<Monotonicity>,
+        observable: bool,
+    ) -> Regulation {
+        return Regulation {
+            regulator,
+            target,
+            monotonicity,
+            observable,
+        };
+    }
+}
+
+impl RegulatoryGraph {
+    /// Create a graph over the given variables without any regulations.
+    ///
+    /// Panics if the names are not unique.
+    pub fn new(names: Vec<String>) -> RegulatoryGraph {
+        for (i, name) in names.iter().enumerate() {
+            if names[..i].contains(name) {
+                panic!("Duplicate variable name `{}`.", name);
+            }
+        }
+        return RegulatoryGraph {
+            names,
+            regulations: Vec::new(),
+            index: HashMap::new(),
+        };
+    }
+
+    /// Infer the regulatory graph of a network: there is an observable regulation for every
+    /// variable on which an update function (semantically) depends. Its monotonicity is
+    /// `None` if the function is neither increasing nor decreasing in the regulator
+    /// (see `BoolExpr::atom_effect`).
+    pub fn from_network(network: &BooleanNetwork) -> RegulatoryGraph {
+        let mut graph = RegulatoryGraph::new(network.names().to_vec());
+        for target in 0..network.num_vars() {
+            let mut effects = network.update_function(target).atom_effects();
+            effects.sort_by_key(|(regulator, _)| *regulator);
+            for (regulator, effect) in effects {
+                let monotonicity = match effect.monotonicity {
+                    Monotonicity::Inessential => continue,
+                    Monotonicity::NonMonotone => None,
+                    monotonicity => Some(monotonicity),
+                };
+                graph.add_regulation(Regulation::new(regulator, target, monotonicity, true));
+            }
+        }
+        return graph;
+    }
+
+    /// The number of variables of this graph.
+    pub fn num_vars(&self) -> usize {
+        return self.names.len();
+    }
+
+    /// The names of all variables (indexed by variables).
+    pub fn names(&self) -> &[String] {
+        return &self.names;
+    }
+
+    /// The index of the variable with the given name (if it exists).
+    pub fn find_variable(&self, name: &str) -> Option<usize> {
+        return self.names.iter().position(|it| it == name);
+    }
+
+    /// Add a new regulation to this graph.
+    ///
+    /// Panics if the regulation refers to a variable which does not exist, or if the same
+    /// pair of variables is already regulated.
+    pub fn add_regulation(&mut self, regulation: Regulation) {
+        let key = (regulation.regulator, regulation.target);
+        if key.0 >= self.num_vars() || key.1 >= self.num_vars() {
+            panic!("Regulation {:?} uses unknown variables.", regulation);
+        }
+        if self.index.contains_key(&key) {
+            panic!(
+                "Regulation {} -> {} already exists.",
+                self.names[key.0], self.names[key.1]
+            );
+        }
+        self.index.insert(key, self.regulations.len());
+        self.regulations.push(regulation);
+    }
+
+    /// The regulation of `target` by `regulator` (if it exists).
+    pub fn regulation(&self, regulator: usize, target: usize) -> Option<&Regulation> {
+        return self
+            .index
+            .get(&(regulator, target))
+            .map(|i| &self.regulations[*i]);
+    }
+
+    /// All regulations (in the order in which they were added).
+    pub fn regulations(&self) -> &[Regulation] {
+        return &self.regulations;
+    }
+
+    /// All regulators of the given `target` (sorted).
+    pub fn regulators(&self, target: usize) -> Vec<usize> {
+        let mut result: Vec<usize> = self
+            .regulations
+            .iter()
+            .filter(|r| r.target == target)
+            .map(|r| r.regulator)
+            .collect();
+        result.sort_unstable();
+        return result;
+    }
+
+    /// All targets of the given `regulator` (sorted).
+    pub fn targets(&self, regulator: usize) -> Vec<usize> {
+        let mut result: Vec<usize> = self
+            .regulations
+            .iter()
+            .filter(|r| r.regulator == regulator)
+            .map(|r| r.target)
+            .collect();
+        result.sort_unstable();
+        return result;
+    }
+
+    /// Check that the update functions of the `network` agree with this graph: every
+    /// function only depends on its declared regulators, depends on all its observable
+    /// regulators, and has the declared monotonicity (where it is known). Errors are
+    /// reported in the order of targets, then regulators.
+    ///
+    /// Panics if the network does not have the same variables as this graph.
+    pub fn validate(&self, network: &BooleanNetwork) -> Result<(), Vec<RegulationError>> {
+        if network.names() != self.names() {
+            panic!("The network and the regulatory graph have different variables.");
+        }
+        let mut errors = Vec::new();
+        for target in 0..self.num_vars() {
+            let effects: HashMap<usize, Monotonicity> = network
+                .update_function(target)
+                .atom_effects()
+                .into_iter()
+                .map(|(regulator, effect)| (regulator, effect.monotonicity))
+                .collect();
+            for regulator in 0..self.num_vars() {
+                let actual = effects
+                    .get(&regulator)
+                    .cloned()
+                    .unwrap_or(Monotonicity::Inessential);
+                let names = || (self.names[regulator].clone(), self.names[target].clone());
+                match self.regulation(regulator, target) {
+                    None if actual != Monotonicity::Inessential => {
+                        let (regulator, target) = names();
+                        errors.push(RegulationError::Undeclared { regulator, target });
+                    }
+                    None => {}
+                    Some(regulation) => {
+                        if actual == Monotonicity::Inessential {
+                            if regulation.observable {
+                                let (regulator, target) = names();
+                                errors.push(RegulationError::NotObservable { regulator, target });
+                            }
+                        } else if let Some(declared) = regulation.monotonicity {
+                            if declared != actual {
+                                let (regulator, target) = names();
+                                errors.push(RegulationError::Monotonicity {
+                                    regulator,
+                                    target,
+                                    declared,
+                                    actual,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        return if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        };
+    }
+}
+
+/// Regulations of a `RegulatoryGraph`, where the edge is a `(regulator, target)` pair.
+impl EdgeLabels for RegulatoryGraph {
+    type Label = Option<Regulation>;
+    type Vertex = IdVertex;
+
+    fn get(&self, edge: (IdVertex, IdVertex)) -> Self::Label {
+        return self.regulation(edge.0.into(), edge.1.into()).cloned();
+    }
+}
+
+/// Names of the variables of a `RegulatoryGraph`.
+impl VertexLabels for RegulatoryGraph {
+    type Label = String;
+    type Vertex = IdVertex;
+
+    fn get(&self, vertex: IdVertex) -> Self::Label {
+        let index: usize = vertex.into();
+        return self.names[index].clone();
+    }
+}
+
+impl Display for RegulationError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        return match self {
+            RegulationError::Undeclared { regulator, target } => {
+                write!(f, "regulation {} -> {} is not declared", regulator, target)
+            }
+            RegulationError::NotObservable { regulator, target } => {
+                write!(
+                    f,
+                    "regulation {} -> {} is not observable",
+                    regulator, target
+                )
+            }
+            RegulationError::Monotonicity {
+                regulator,
+                target,
+                declared,
+                actual,
+            } => write!(
+                f,
+                "regulation {} -> {} is {}, but declared as {}",
+                regulator,
+                target,
+                describe(*actual),
+                describe(*declared)
+            ),
+        };
+    }
+}
+
+/// **(internal)** Name of a monotonicity for error messages.
+fn describe(monotonicity: Monotonicity) -> &'static str {
+    return match monotonicity {
+        Monotonicity::Positive => "positive",
+        Monotonicity::Negative => "negative",
+        Monotonicity::NonMonotone => "non-monotone",
+        Monotonicity::Inessential => "inessential",
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::graphs::{EdgeLabels, IdVertex, VertexLabels};
+    use crate::logic::Monotonicity;
+    use crate::networks::test_util::network;
+    use crate::networks::{Regulation, RegulationError, RegulatoryGraph};
+
+    #[test]
+    fn regulatory_graph_inferred() {
+        let network = network(&["x1 & !x2", "x0 ^ x2", "x2 | x0 & !x0"]);
+        let graph = RegulatoryGraph::from_network(&network);
+        assert!(graph.validate(&network).is_ok());
+        assert_eq!(vec![1, 2], graph.regulators(0));
+        assert_eq!(vec![0, 1, 2], graph.targets(2));
+        // `x0` appears in the update function of `x2`, but it is not essential.
+        assert_eq!(vec![2], graph.regulators(2));
+        let expected = vec![
+            Regulation::new(1, 0, Some(Monotonicity::Positive), true),
+            Regulation::new(2, 0, Some(Monotonicity::Negative), true),
+            Regulation::new(0, 1, None, true),
+            Regulation::new(2, 1, None, true),
+            Regulation::new(2, 2, Some(Monotonicity::Positive), true),
+        ];
+        assert_eq!(expected, graph.regulations());
+    }
+
+    #[test]
+    fn regulatory_graph_labels() {
+        let graph = RegulatoryGraph::from_network(&network(&["!x1", "x1"]));
+        let edge =
+            |r: usize, t: usize| EdgeLabels::get(&graph, (IdVertex::from(r), IdVertex::from(t)));
+        assert_eq!(None, edge(0, 1));
+        assert_eq!(
+            Some(Monotonicity::Negative),
+            edge(1, 0).unwrap().monotonicity
+        );
+        assert_eq!("x1", VertexLabels::get(&graph, IdVertex::from(1)));
+        assert_eq!(Some(1), graph.find_variable("x1"));
+    }
+
+    #[test]
+    fn regulatory_graph_validation() {
+        let network = network(&["x1 & !x2", "x0 ^ x2", "x2"]);
+        let mut graph = RegulatoryGraph::new(network.names().to_vec());
+        let declared = vec![
+            Regulation::new(1, 0, Some(Monotonicity::Positive), true),
+            Regulation::new(2, 0, Some(Monotonicity::Positive), false),
+            Regulation::new(0, 1, Some(Monotonicity::NonMonotone), true),
+            Regulation::new(0, 2, None, true),
+            Regulation::new(1, 2, Some(Monotonicity::Negative), false),
+            Regulation::new(2, 2, None, false),
+        ];
+        for regulation in declared {
+            graph.add_regulation(regulation);
+        }
+        let name = |i: usize| format!("x{}", i);
+        let expected = vec![
+            RegulationError::Monotonicity {
+                regulator: name(2),
+                target: name(0),
+                declared: Monotonicity::Positive,
+                actual: Monotonicity::Negative,
+            },
+            RegulationError::Undeclared {
+                regulator: name(2),
+                target: name(1),
+            },
+            RegulationError::NotObservable {
+                regulator: name(0),
+                target: name(2),
+            },
+        ];
+        assert_eq!(Err(expected), graph.validate(&network));
+    }
+
+    #[test]
+    #[should_panic]
+    fn regulatory_graph_duplicate_regulation() {
+        let mut graph = RegulatoryGraph::new(vec!["a".to_string()]);
+        graph.add_regulation(Regulation::new(0, 0, None, true));
+        graph.add_regulation(Regulation::new(0, 0, None, false));
+    }
+}
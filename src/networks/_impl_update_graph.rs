@@ -0,0 +1,331 @@
+use super::{UpdateEdges, UpdateGraph, UpdateScheme};
+use crate::collections::bitvectors::{BitVector, BitVector58};
+use crate::graph::{EvolutionOperator, Graph};
+use crate::logic::BoolExpr;
+use crate::{IdState, IdStateRange};
+use std::marker::PhantomData;
+
+impl<'a> UpdateGraph<'a> {
+    /// Forward edges of this graph over `BitVector58` states.
+    ///
+    /// Panics if the network has more variables than a `BitVector58` can hold.
+    pub fn fwd_bit_vectors(&self) -> UpdateEdges<'a, BitVector58> {
+        return self.bit_vector_edges(true);
+    }
+
+    /// Backward edges of this graph over `BitVector58` states.
+    ///
+    /// Panics if the network has more variables than a `BitVector58` can hold.
+    pub fn bwd_bit_vectors(&self) -> UpdateEdges<'a, BitVector58> {
+        return self.bit_vector_edges(false);
+    }
+
+    /// **(internal)** Edges over `BitVector58` states in the given direction.
+    fn bit_vector_edges(&self, forward: bool) -> UpdateEdges<'a, BitVector58> {
+        let num_vars = self.network.num_vars();
+        if num_vars > BitVector58::max_length() {
+            panic!(
+                "Network with {} variables is too large for a BitVector58 graph.",
+                num_vars
+            );
+        }
+        return self.edges(forward);
+    }
+
+    /// **(internal)** Edges over `S` states in the given direction.
+    fn edges<S>(&self, forward: bool) -> UpdateEdges<'a, S> {
+        return UpdateEdges {
+            network: self.network,
+            scheme: self.scheme.clone(),
+            forward,
+            _state: PhantomData,
+        };
+    }
+}
+
+impl<'a> Graph for UpdateGraph<'a> {
+    type State = IdState;
+    type States = IdStateRange;
+    type FwdEdges = UpdateEdges<'a>;
+    type BwdEdges = UpdateEdges<'a>;
+
+    fn states(&self) -> Self::States {
+        return IdStateRange::new(1 << self.network.num_vars());
+    }
+
+    fn fwd(&self) -> Self::FwdEdges {
+        return self.edges(true);
+    }
+
+    fn bwd(&self) -> Self::BwdEdges {
+        return self.edges(false);
+    }
+}
+
+impl EvolutionOperator for UpdateEdges<'_> {
+    type State = IdState;
+    type Iterator = std::vec::IntoIter<IdState>;
+
+    fn step(&self, current: Self::State) -> Self::Iterator {
+        return self.targets(current).into_iter();
+    }
+}
+
+impl EvolutionOperator for UpdateEdges<'_, BitVector58> {
+    type State = BitVector58;
+    type Iterator = std::vec::IntoIter<BitVector58>;
+
+    fn step(&self, current: Self::State) -> Self::Iterator {
+        let num_vars = self.network.num_vars();
+        let state = current
+            .ones()
+            .into_iter()
+            .fold(IdState::from(0), |state, v| state.flip_bit(v));
+        let states: Vec<BitVector58> = self
+            .targets(state)
+            .into_iter()
+            .map(|s| {
+                BitVector58::from_ones(num_vars, (0..num_vars).filter(|v| s.get_bit(*v)).collect())
+            })
+            .collect();
+        return states.into_iter();
+    }
+}
+
+impl<S> UpdateEdges<'_, S> {
+    /// **(internal)** Successors or predecessors of `state`, depending on the direction
+    /// of these edges.
+    fn targets(&self, state: IdState) -> Vec<IdState> {
+        return if self.forward {
+            self.successors(state)
+        } else {
+            self.predecessors(state)
+        };
+    }
+
+    /// **(internal)** All successors of `state` under the update scheme.
+    fn successors(&self, state: IdState) -> Vec<IdState> {
+        let network = self.network;
+        let enabled: Vec<usize> = (0..network.num_vars())
+            .filter(|v| network.is_enabled(*v, state))
+            .collect();
+        return match &self.scheme {
+            UpdateScheme::Asynchronous => enabled.iter().map(|v| state.flip_bit(*v)).collect(),
+            UpdateScheme::Synchronous => vec![flip_all(state, enabled.into_iter())],
+            UpdateScheme::GeneralizedAsynchronous => (1..(1usize << enabled.len()))
+                .map(|subset| {
+                    let variables = enabled
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| (subset >> i) & 1 == 1)
+                        .map(|(_, v)| *v);
+                    flip_all(state, variables)
+                })
+                .collect(),
+            UpdateScheme::Priority(classes) => classes
+                .iter()
+                .map(|class| {
+                    class
+                        .iter()
+                        .filter(|v| enabled.contains(v))
+                        .map(|v| state.flip_bit(*v))
+                        .collect::<Vec<_>>()
+                })
+                .find(|successors| !successors.is_empty())
+                .unwrap_or_default(),
+        };
+    }
+
+    /// **(internal)** All predecessors of `state` under the update scheme.
+    fn predecessors(&self, state: IdState) -> Vec<IdState> {
+        let network = self.network;
+        let num_vars = network.num_vars();
+        // The value of the update function of `v` has to be equal to the value of `v`
+        // in `state`.
+        let updated = |v: usize| literal(network.update_function(v).clone(), state.get_bit(v));
+        // The variable `v` of the predecessor has the same value as in `state`.
+        let unchanged = |v: usize| literal(BoolExpr::Atom(v), state.get_bit(v));
+        let predecessors = match &self.scheme {
+            UpdateScheme::Asynchronous | UpdateScheme::Priority(_) => {
+                // Predecessors differ in exactly one variable.
+                return (0..num_vars)
+                    .map(|v| state.flip_bit(v))
+                    .filter(|source| self.successors(*source).contains(&state))
+                    .collect();
+            }
+            UpdateScheme::Synchronous => BoolExpr::And((0..num_vars).map(updated).collect()),
+            UpdateScheme::GeneralizedAsynchronous => {
+                let mut conditions: Vec<BoolExpr<usize>> = (0..num_vars)
+                    .map(|v| BoolExpr::Or(vec![unchanged(v), updated(v)]))
+                    .collect();
+                let changed = (0..num_vars).map(|v| BoolExpr::Not(Box::new(unchanged(v))));
+                conditions.push(BoolExpr::Or(changed.collect()));
+                BoolExpr::And(conditions)
+            }
+        };
+        let atoms: Vec<usize> = (0..num_vars).collect();
+        let mut result: Vec<IdState> = predecessors
+            .satisfying_cubes(&atoms)
+            .into_iter()
+            .flat_map(|cube| cube_states(&cube))
+            .collect();
+        result.sort();
+        return result;
+    }
+}
+
+/// **(internal)** Flip all given variables of the `state`.
+fn flip_all<I: Iterator<Item = usize>>(state: IdState, variables: I) -> IdState {
+    return variables.fold(state, |state, v| state.flip_bit(v));
+}
+
+/// **(internal)** The given expression if `value` is true, otherwise its negation.
+fn literal(expr: BoolExpr<usize>, value: bool) -> BoolExpr<usize> {
+    return if value {
+        expr
+    } else {
+        BoolExpr::Not(Box::new(expr))
+    };
+}
+
+/// **(internal)** All states in a cube over variables `0..cube.len()`.
+fn cube_states(cube: &[Option<bool>]) -> Vec<IdState> {
+    let mut states = vec![0usize];
+    for (v, value) in cube.iter().enumerate() {
+        states = match value {
+            Some(false) => states,
+            Some(true) => states.into_iter().map(|s| s | (1 << v)).collect(),
+            None => states
+                .into_iter()
+                .flat_map(|s| vec![s, s | (1 << v)])
+                .collect(),
+        };
+    }
+    return states.into_iter().map(IdState::from).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::bitvectors::{BitVector, BitVector58};
+    use crate::graph::{EvolutionOperator, Graph};
+    use crate::logic::BoolExpr;
+    use crate::networks::test_util::random_network;
+    use crate::networks::{BooleanNetwork, UpdateScheme};
+    use crate::testing::Random;
+    use crate::IdState;
+    use std::collections::HashSet;
+
+    fn schemes() -> Vec<UpdateScheme> {
+        return vec![
+            UpdateScheme::Asynchronous,
+            UpdateScheme::Synchronous,
+            UpdateScheme::GeneralizedAsynchronous,
+            UpdateScheme::Priority(vec![vec![3], vec![0, 4], vec![2, 1]]),
+        ];
+    }
+
+    #[test]
+    fn update_graph_successors() {
+        let mut random = Random::new(22);
+        let network = random_network(&mut random, 5);
+        let async_graph = network.async_graph();
+        for state in async_graph.states() {
+            let enabled: Vec<usize> = (0..5).filter(|v| network.is_enabled(*v, state)).collect();
+            let successors = |scheme: UpdateScheme| {
+                let graph = network.update_graph(scheme);
+                let result: Vec<IdState> = graph.fwd().step(state).collect();
+                result
+            };
+            let asynchronous: Vec<IdState> = async_graph.fwd().step(state).collect();
+            assert_eq!(asynchronous, successors(UpdateScheme::Asynchronous));
+            let next = (0..5).fold(0, |s, v| s | (network.next_value(v, state) as usize) << v);
+            assert_eq!(
+                vec![IdState::from(next)],
+                successors(UpdateScheme::Synchronous)
+            );
+            let general = successors(UpdateScheme::GeneralizedAsynchronous);
+            assert_eq!((1 << enabled.len()) - 1, general.len());
+            assert!(asynchronous.iter().all(|s| general.contains(s)));
+            let priority = successors(UpdateScheme::Priority(vec![(0..5).collect()]));
+            assert_eq!(asynchronous, priority);
+        }
+    }
+
+    #[test]
+    fn update_graph_priority() {
+        // x0 = !x0, x1 = !x1: x1 can only change when x0 is stable, which never happens.
+        let expr = |f: &str| f.parse::<BoolExpr<String>>().unwrap();
+        let functions = vec![expr("!x0"), expr("!x1")]
+            .into_iter()
+            .map(|f| f.map_atoms(|atom| atom[1..].parse::<usize>().unwrap()))
+            .collect();
+        let network = BooleanNetwork::new(vec!["x0".to_string(), "x1".to_string()], functions);
+        let graph = network.update_graph(UpdateScheme::Priority(vec![vec![0], vec![1]]));
+        for state in graph.states() {
+            let successors: Vec<IdState> = graph.fwd().step(state).collect();
+            assert_eq!(vec![state.flip_bit(0)], successors);
+            let predecessors: Vec<IdState> = graph.bwd().step(state).collect();
+            assert_eq!(vec![state.flip_bit(0)], predecessors);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn update_graph_invalid_priority() {
+        let mut random = Random::new(0);
+        let network = random_network(&mut random, 3);
+        network.update_graph(UpdateScheme::Priority(vec![vec![0, 1], vec![1, 2]]));
+    }
+
+    #[test]
+    fn update_graph_fwd_bwd_consistent() {
+        let mut random = Random::new(2022);
+        for _ in 0..5 {
+            let network = random_network(&mut random, 5);
+            for scheme in schemes() {
+                let graph = network.update_graph(scheme.clone());
+                let mut fwd_edges = HashSet::new();
+                let mut bwd_edges = HashSet::new();
+                for state in graph.states() {
+                    fwd_edges.extend(graph.fwd().step(state).map(|s| (state, s)));
+                    bwd_edges.extend(graph.bwd().step(state).map(|s| (s, state)));
+                }
+                assert_eq!(fwd_edges, bwd_edges, "{:?}", scheme);
+            }
+        }
+    }
+
+    #[test]
+    fn update_graph_bit_vectors() {
+        let mut random = Random::new(58);
+        let network = random_network(&mut random, 5);
+        let bit_vector = |state: IdState| {
+            BitVector58::from_ones(5, (0..5).filter(|v| state.get_bit(*v)).collect())
+        };
+        for scheme in schemes() {
+            let graph = network.update_graph(scheme);
+            for state in graph.states() {
+                let successors: Vec<BitVector58> =
+                    graph.fwd().step(state).map(bit_vector).collect();
+                let bv_successors: Vec<BitVector58> =
+                    graph.fwd_bit_vectors().step(bit_vector(state)).collect();
+                assert_eq!(successors, bv_successors);
+                let predecessors: Vec<BitVector58> =
+                    graph.bwd().step(state).map(bit_vector).collect();
+                let bv_predecessors: Vec<BitVector58> =
+                    graph.bwd_bit_vectors().step(bit_vector(state)).collect();
+                assert_eq!(predecessors, bv_predecessors);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn update_graph_bit_vectors_too_large() {
+        let mut random = Random::new(0);
+        let network = random_network(&mut random, 59);
+        network
+            .update_graph(UpdateScheme::Synchronous)
+            .fwd_bit_vectors();
+    }
+}
@@ -0,0 +1,351 @@
+//! Boolean networks and their state transition graphs.
+//!
+//! A `BooleanNetwork` consists of named variables, each with an update function given as
+//! a `BoolExpr<usize>` where atoms are indices of variables. States of the network are
+//! represented by `IdState`s, where the `i`-th bit is the value of the `i`-th variable.
+//! Networks of any size can be created (e.g. read from `.bnet` files), but the methods
+//! which work with states, including the state transition graphs, require fewer variables
+//! than the width of `usize`.
+//!
+//! ```rust
+//! use biodivine_lib_std::networks::BooleanNetwork;
+//! use biodivine_lib_std::logic::BoolExpr;
+//! use biodivine_lib_std::IdState;
+//! // A toggle switch: `a` and `b` inhibit each other.
+//! let network = BooleanNetwork::new(
+//!     vec!["a".to_string(), "b".to_string()],
+//!     vec![
+//!         BoolExpr::Not(Box::new(BoolExpr::Atom(1))),
+//!         BoolExpr::Not(Box::new(BoolExpr::Atom(0))),
+//!     ],
+//! );
+//! assert_eq!(Some(1), network.find_variable("b"));
+//! // In state `a = 1, b = 0`, both update functions agree with the current values.
+//! assert!(network.is_fixed_point(IdState::from(0b01)));
+//! assert!(network.next_value(1, IdState::from(0b00)));
+//! ```
+//!
+//! ### Asynchronous semantics
+//!
+//! In the asynchronous state transition graph (`AsyncGraph`), a state has one successor for
+//! every variable whose update function disagrees with its current value: the successor
+//! is the state with this variable flipped. The graph implements `graph::Graph`, computing
+//! the edges on the fly (both forward and backward):
+//!
+//! ```rust
+//! use biodivine_lib_std::networks::BooleanNetwork;
+//! use biodivine_lib_std::graph::{EvolutionOperator, Graph};
+//! use biodivine_lib_std::logic::BoolExpr;
+//! use biodivine_lib_std::IdState;
+//! let network = BooleanNetwork::new(
+//!     vec!["a".to_string(), "b".to_string()],
+//!     vec![
+//!         BoolExpr::Not(Box::new(BoolExpr::Atom(1))),
+//!         BoolExpr::Not(Box::new(BoolExpr::Atom(0))),
+//!     ],
+//! );
+//! let graph = network.async_graph();
+//! assert_eq!(4, graph.states().count());
+//! let successors: Vec<IdState> = graph.fwd().step(IdState::from(0b00)).collect();
+//! assert_eq!(vec![IdState::from(0b01), IdState::from(0b10)], successors);
+//! let predecessors: Vec<IdState> = graph.bwd().step(IdState::from(0b01)).collect();
+//! assert_eq!(vec![IdState::from(0b00), IdState::from(0b11)], predecessors);
+//! ```
+//!
+//! ### Other update schemes
+//!
+//! `BooleanNetwork::update_graph` creates a state transition graph under any of the
+//! supported `UpdateScheme`s (synchronous, generalized asynchronous, priority classes or
+//! again asynchronous). All graphs provide both forward and backward edges. Predecessors in
+//! the synchronous and generalized asynchronous graphs are computed symbolically, by
+//! enumerating the models of a formula describing all possible predecessors.
+//!
+//! The states of an `UpdateGraph` are `IdState`s, but its edges are also available as
+//! an `EvolutionOperator` over `BitVector58`s (for networks with at most 58 variables).
+//!
+//! ```rust
+//! use biodivine_lib_std::collections::bitvectors::{BitVector, BitVector58};
+//! use biodivine_lib_std::networks::{BooleanNetwork, UpdateScheme};
+//! use biodivine_lib_std::graph::{EvolutionOperator, Graph};
+//! use biodivine_lib_std::logic::BoolExpr;
+//! use biodivine_lib_std::IdState;
+//! let network = BooleanNetwork::new(
+//!     vec!["a".to_string(), "b".to_string()],
+//!     vec![
+//!         BoolExpr::Not(Box::new(BoolExpr::Atom(1))),
+//!         BoolExpr::Not(Box::new(BoolExpr::Atom(0))),
+//!     ],
+//! );
+//! let sync = network.update_graph(UpdateScheme::Synchronous);
+//! let successors: Vec<IdState> = sync.fwd().step(IdState::from(0b00)).collect();
+//! assert_eq!(vec![IdState::from(0b11)], successors);
+//! let general = network.update_graph(UpdateScheme::GeneralizedAsynchronous);
+//! assert_eq!(3, general.fwd().step(IdState::from(0b00)).count());
+//! // Variable `b` has a higher priority than `a`.
+//! let priority = network.update_graph(UpdateScheme::Priority(vec![vec![1], vec![0]]));
+//! let successors: Vec<IdState> = priority.fwd().step(IdState::from(0b00)).collect();
+//! assert_eq!(vec![IdState::from(0b10)], successors);
+//! let successors: Vec<BitVector58> = sync.fwd_bit_vectors().step(BitVector58::empty(2)).collect();
+//! assert_eq!(vec![BitVector58::from_ones(2, vec![0, 1])], successors);
+//! ```
+//!
+//! ### Parametrised networks
+//!
+//! In a `ParametrisedNetwork`, update functions can contain uninterpreted functions
+//! (parameters) applied to network variables, as in AEON models. The atoms of such update
+//! functions are `ParamAtom`s. A valuation of parameters assigns a value to every row of
+//! the truth table of every parameter, where each row is represented by one BDD variable.
+//! Sets of parameter valuations are thus represented symbolically as `BddParams`.
+//!
+//! Note that parameters are not part of `BoolExpr` itself: an application of a parameter
+//! is a single `ParamAtom::Parameter` atom of a `BoolExpr<ParamAtom>`. As a consequence,
+//! the text parser of `BoolExpr` cannot read applications such as `f(a, b)`, and
+//! parametrised update functions have to be constructed programmatically.
+//!
+//! The asynchronous state transition graph of the network (`ParamAsyncGraph`) implements
+//! `param_graph::Graph`: every edge is labelled by the set of parameter valuations for which
+//! it exists.
+//!
+//! ```rust
+//! use biodivine_lib_std::collections::bitvectors::ArrayBitVector;
+//! use biodivine_lib_std::networks::{ParamAtom, ParametrisedNetwork};
+//! use biodivine_lib_std::param_graph::{EvolutionOperator, Graph, Params};
+//! use biodivine_lib_std::logic::BoolExpr;
+//! use biodivine_lib_std::IdState;
+//! // `a` is updated by an unknown function `f` of `b`, `b` copies `a`.
+//! let network = ParametrisedNetwork::new(
+//!     vec!["a".to_string(), "b".to_string()],
+//!     vec![("f".to_string(), 1)],
+//!     vec![
+//!         BoolExpr::Atom(ParamAtom::Parameter { parameter: 0, args: vec![1] }),
+//!         BoolExpr::Atom(ParamAtom::Variable(0)),
+//!     ],
+//! );
+//! // Two BDD variables encode `f(false)` and `f(true)`.
+//! assert_eq!(2, network.num_parameter_vars());
+//! let graph = network.async_graph();
+//! let successors: Vec<_> = graph.fwd().step(IdState::from(0b00)).collect();
+//! // `a` can become true only if `f(false)` is true (in half of all valuations).
+//! assert_eq!(1, successors.len());
+//! assert_eq!(IdState::from(0b01), successors[0].0);
+//! assert_eq!(Some(2), successors[0].1.count().to_u64());
+//! // For the valuation where `f` is the identity, `00` is a fixed point.
+//! let identity = network.instantiate(&ArrayBitVector::from(vec![false, true]));
+//! assert!(identity.is_fixed_point(IdState::from(0b00)));
+//! ```
+//!
+//! ### Regulatory graphs
+//!
+//! A `RegulatoryGraph` describes which variables regulate which, with an optional
+//! monotonicity (activation or inhibition) and an observability flag (the target actually
+//! depends on the regulator) for every `Regulation`. It can be built explicitly and
+//! validated against the update functions of a network, or inferred from the functions.
+//! The regulations are available as `EdgeLabels` over `IdVertex`s.
+//!
+//! ```rust
+//! use biodivine_lib_std::collections::graphs::{EdgeLabels, IdVertex};
+//! use biodivine_lib_std::logic::{BoolExpr, Monotonicity};
+//! use biodivine_lib_std::networks::{BooleanNetwork, Regulation, RegulatoryGraph};
+//! let names = vec!["a".to_string(), "b".to_string()];
+//! // a = !b, b = a | b
+//! let network = BooleanNetwork::new(
+//!     names.clone(),
+//!     vec![
+//!         BoolExpr::Not(Box::new(BoolExpr::Atom(1))),
+//!         BoolExpr::Or(vec![BoolExpr::Atom(0), BoolExpr::Atom(1)]),
+//!     ],
+//! );
+//! let inferred = RegulatoryGraph::from_network(&network);
+//! assert_eq!(3, inferred.regulations().len());
+//! let regulation = inferred.get((IdVertex::from(1), IdVertex::from(0))).unwrap();
+//! assert_eq!(Some(Monotonicity::Negative), regulation.monotonicity);
+//! // A declared graph with a wrong sign and a missing regulation.
+//! let mut declared = RegulatoryGraph::new(names);
+//! declared.add_regulation(Regulation::new(1, 0, Some(Monotonicity::Positive), true));
+//! declared.add_regulation(Regulation::new(0, 1, None, true));
+//! let errors = declared.validate(&network).unwrap_err();
+//! assert_eq!(2, errors.len());
+//! assert_eq!("regulation b -> a is negative, but declared as positive", errors[0].to_string());
+//! assert_eq!("regulation b -> b is not declared", errors[1].to_string());
+//! ```
+//!
+//! ### BoolNet format
+//!
+//! Networks can be read from and written to the `.bnet` format used by BoolNet and many
+//! public model repositories. Errors (syntax errors, duplicate targets and undeclared
+//! variables) are reported as `ParseError`s with a line and a column:
+//!
+//! ```rust
+//! use biodivine_lib_std::networks::BooleanNetwork;
+//! let network = BooleanNetwork::from_bnet("targets, factors\nA, B & !C\nB, A\nC, 1\n").unwrap();
+//! assert_eq!(Some(2), network.find_variable("C"));
+//! assert_eq!("targets, factors\nA, B & !C\nB, A\nC, 1\n", network.to_bnet());
+//! let error = BooleanNetwork::from_bnet("A, B\nB, D | A\n").unwrap_err();
+//! assert_eq!((2, 4, "`D`"), (error.line, error.column, error.found.as_str()));
+//! ```
+
+use crate::collections::bdd::{BddManager, BddSet};
+use crate::collections::bitvectors::ArrayBitVector;
+use crate::logic::{BoolExpr, Monotonicity};
+use crate::IdState;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+mod _impl_async_graph;
+mod _impl_bnet;
+mod _impl_boolean_network;
+mod _impl_param_async_graph;
+mod _impl_parametrised_network;
+mod _impl_regulatory_graph;
+mod _impl_update_graph;
+#[cfg(test)]
+mod test_util;
+
+/// A Boolean network with named variables. The update function of every variable is
+/// a `BoolExpr` over variable indices. For usage examples, see module description.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BooleanNetwork {
+    names: Vec<String>,
+    functions: Vec<BoolExpr<usize>>,
+}
+
+/// The asynchronous state transition graph of a `BooleanNetwork`.
+#[derive(Clone, Copy, Debug)]
+pub struct AsyncGraph<'a> {
+    network: &'a BooleanNetwork,
+}
+
+/// Forward (successor) or backward (predecessor) edges of an `AsyncGraph`.
+#[derive(Clone, Copy, Debug)]
+pub struct AsyncEdges<'a> {
+    network: &'a BooleanNetwork,
+    forward: bool,
+}
+
+/// An iterator over the successors or predecessors of a state in an `AsyncGraph`.
+#[derive(Clone, Debug)]
+pub struct AsyncEdgeIterator<'a> {
+    edges: AsyncEdges<'a>,
+    state: IdState,
+    /// The next variable to be tested.
+    next: usize,
+}
+
+/// The rule which determines which variables are updated in a transition of a
+/// `BooleanNetwork`. A variable is *enabled* in a state if its update function disagrees
+/// with its current value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum UpdateScheme {
+    /// Exactly one enabled variable is updated (same as `AsyncGraph`).
+    Asynchronous,
+    /// All variables are updated at once. Every state has exactly one successor (fixed
+    /// points have a self-loop).
+    Synchronous,
+    /// Any non-empty subset of the enabled variables is updated at once.
+    GeneralizedAsynchronous,
+    /// Variables are partitioned into priority classes (the first class has the highest
+    /// priority). One enabled variable of the highest priority class which contains
+    /// an enabled variable is updated.
+    Priority(Vec<Vec<usize>>),
+}
+
+/// The state transition graph of a `BooleanNetwork` under a given `UpdateScheme`.
+#[derive(Clone, Debug)]
+pub struct UpdateGraph<'a> {
+    network: &'a BooleanNetwork,
+    scheme: UpdateScheme,
+}
+
+/// Forward (successor) or backward (predecessor) edges of an `UpdateGraph`.
+///
+/// The edges are an `EvolutionOperator` over `IdState`s (as returned by `UpdateGraph::fwd`
+/// and `UpdateGraph::bwd`), or over `BitVector58`s (as returned by
+/// `UpdateGraph::fwd_bit_vectors` and `UpdateGraph::bwd_bit_vectors`).
+#[derive(Clone, Debug)]
+pub struct UpdateEdges<'a, S = IdState> {
+    network: &'a BooleanNetwork,
+    scheme: UpdateScheme,
+    forward: bool,
+    _state: PhantomData<S>,
+}
+
+/// An atom of an update function of a `ParametrisedNetwork`. Applications of parameters
+/// are atoms rather than a special kind of `BoolExpr` node, so that `BoolExpr` and all
+/// its algorithms stay unchanged.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ParamAtom {
+    /// The value of a network variable.
+    Variable(usize),
+    /// An uninterpreted function (parameter) applied to the values of network variables.
+    Parameter { parameter: usize, args: Vec<usize> },
+}
+
+/// A set of parameter valuations of a `ParametrisedNetwork`, represented by a BDD over
+/// the rows of the truth tables of all parameters.
+pub type BddParams = BddSet<ArrayBitVector>;
+
+/// A Boolean network whose update functions can contain uninterpreted functions
+/// (parameters). For usage examples, see module description.
+#[derive(Clone, Debug)]
+pub struct ParametrisedNetwork {
+    names: Vec<String>,
+    /// Names and arities of all parameters.
+    parameters: Vec<(String, usize)>,
+    functions: Vec<BoolExpr<ParamAtom>>,
+    /// The BDD variable of the first row of the truth table of every parameter.
+    offsets: Vec<usize>,
+    manager: Rc<RefCell<BddManager>>,
+}
+
+/// The asynchronous state transition graph of a `ParametrisedNetwork`.
+#[derive(Clone, Copy, Debug)]
+pub struct ParamAsyncGraph<'a> {
+    network: &'a ParametrisedNetwork,
+}
+
+/// Forward (successor) or backward (predecessor) edges of a `ParamAsyncGraph`.
+#[derive(Clone, Copy, Debug)]
+pub struct ParamAsyncEdges<'a> {
+    network: &'a ParametrisedNetwork,
+    forward: bool,
+}
+
+/// A regulation of the `target` variable by the `regulator` variable.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Regulation {
+    pub regulator: usize,
+    pub target: usize,
+    /// `Positive` for an activation, `Negative` for an inhibition, `None` if unknown.
+    pub monotonicity: Option<Monotonicity>,
+    /// True if the update function of the target must depend on the regulator.
+    pub observable: bool,
+}
+
+/// A directed graph of `Regulation`s between named variables. For usage examples, see
+/// module description.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegulatoryGraph {
+    names: Vec<String>,
+    regulations: Vec<Regulation>,
+    /// Position of every `(regulator, target)` pair in `regulations`.
+    index: HashMap<(usize, usize), usize>,
+}
+
+/// A disagreement between a `RegulatoryGraph` and the update functions of a network,
+/// as reported by `RegulatoryGraph::validate`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RegulationError {
+    /// The update function of the target depends on a regulator which is not declared.
+    Undeclared { regulator: String, target: String },
+    /// The regulation is observable, but the update function does not depend on the regulator.
+    NotObservable { regulator: String, target: String },
+    /// The update function has a different monotonicity in the regulator than declared.
+    Monotonicity {
+        regulator: String,
+        target: String,
+        declared: Monotonicity,
+        actual: Monotonicity,
+    },
+}
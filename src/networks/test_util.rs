@@ -0,0 +1,50 @@
+//! **(internal)** Network fixtures shared by the tests of this module.
+
+use crate::logic::BoolExpr;
+use crate::networks::{BooleanNetwork, ParamAtom, ParametrisedNetwork};
+use crate::testing::{ExprGenerator, Random};
+
+/// **(internal)** A network with variables `x0, x1, ...`, where the update function of
+/// `x{i}` is parsed from `functions[i]`.
+pub(super) fn network(functions: &[&str]) -> BooleanNetwork {
+    let names: Vec<String> = (0..functions.len()).map(|i| format!("x{}", i)).collect();
+    let functions = functions
+        .iter()
+        .map(|f| {
+            let expr: BoolExpr<String> = f.parse().unwrap();
+            expr.map_atoms(|atom| names.iter().position(|n| n == atom).unwrap())
+        })
+        .collect();
+    return BooleanNetwork::new(names, functions);
+}
+
+/// **(internal)** A network over `num_vars` variables with random update functions.
+pub(super) fn random_network(random: &mut Random, num_vars: usize) -> BooleanNetwork {
+    let generator = ExprGenerator::new(num_vars, 3);
+    let names = (0..num_vars).map(|i| format!("x{}", i)).collect();
+    let functions = (0..num_vars).map(|_| generator.generate(random)).collect();
+    return BooleanNetwork::new(names, functions);
+}
+
+/// **(internal)** A random network over 4 variables with parameters `p(x0)` and `q(x1, x3)`.
+pub(super) fn random_parametrised_network(random: &mut Random) -> ParametrisedNetwork {
+    let generator = ExprGenerator::new(6, 3);
+    let functions = (0..4)
+        .map(|_| {
+            generator.generate(random).map_atoms(|atom| match atom {
+                4 => ParamAtom::Parameter {
+                    parameter: 0,
+                    args: vec![0],
+                },
+                5 => ParamAtom::Parameter {
+                    parameter: 1,
+                    args: vec![1, 3],
+                },
+                v => ParamAtom::Variable(*v),
+            })
+        })
+        .collect();
+    let names = (0..4).map(|i| format!("x{}", i)).collect();
+    let parameters = vec![("p".to_string(), 1), ("q".to_string(), 2)];
+    return ParametrisedNetwork::new(names, parameters, functions);
+}
@@ -0,0 +1,220 @@
+use super::{ExprGenerator, Random};
+use crate::logic::{BoolExpr, BoolOp, Monotonicity};
+
+impl ExprGenerator {
+    /// Create a generator of expressions over atoms `0..atom_count` with at most the given
+    /// `depth` (a single leaf has depth zero). All operators have weight `1`, as well as the
+    /// leaves, and there are no constants and no monotonicity constraints.
+    pub fn new(atom_count: usize, depth: usize) -> ExprGenerator {
+        let ops = [
+            BoolOp::And,
+            BoolOp::Or,
+            BoolOp::Xor,
+            BoolOp::Iff,
+            BoolOp::Imp,
+        ];
+        return ExprGenerator {
+            atom_count,
+            depth,
+            negation_weight: 1,
+            op_weights: ops.iter().map(|op| (*op, 1)).collect(),
+            leaf_weight: 1,
+            constant_probability: 0.0,
+            monotonicity: vec![None; atom_count],
+        };
+    }
+
+    /// Set the relative weight of the binary operator `op`.
+    pub fn with_op_weight(mut self, op: BoolOp, weight: u32) -> ExprGenerator {
+        for (it, it_weight) in self.op_weights.iter_mut() {
+            if *it == op {
+                *it_weight = weight;
+            }
+        }
+        return self;
+    }
+
+    /// Set the relative weight of negations.
+    pub fn with_negation_weight(mut self, weight: u32) -> ExprGenerator {
+        self.negation_weight = weight;
+        return self;
+    }
+
+    /// Set the relative weight of leaves above the maximal depth. With zero, all leaves
+    /// are at the maximal depth.
+    pub fn with_leaf_weight(mut self, weight: u32) -> ExprGenerator {
+        self.leaf_weight = weight;
+        return self;
+    }
+
+    /// Set the probability that a leaf is a constant instead of an atom.
+    pub fn with_constant_probability(mut self, probability: f64) -> ExprGenerator {
+        self.constant_probability = probability;
+        return self;
+    }
+
+    /// Constrain the polarity of the given `atom`. `Positive` and `Negative` atoms only
+    /// appear with that polarity and `Inessential` atoms do not appear at all. `NonMonotone`
+    /// removes the constraint (i.e. the atom is not guaranteed to be non-monotone).
+    ///
+    /// Panics if the atom is out of range.
+    pub fn with_monotonicity(mut self, atom: usize, monotonicity: Monotonicity) -> ExprGenerator {
+        self.monotonicity[atom] = match monotonicity {
+            Monotonicity::NonMonotone => None,
+            _ => Some(monotonicity),
+        };
+        return self;
+    }
+
+    /// Generate a new random expression.
+    pub fn generate(&self, random: &mut Random) -> BoolExpr<usize> {
+        return self.node(random, self.depth, Some(true));
+    }
+
+    /// **(internal)** Generate a node with at most the given `depth`. The `polarity` is `true`
+    /// (`false`) if the node appears positively (negatively) in the whole expression, or
+    /// `None` if it appears below `Xor`/`Iff`.
+    fn node(&self, random: &mut Random, depth: usize, polarity: Option<bool>) -> BoolExpr<usize> {
+        if depth == 0 {
+            return self.leaf(random, polarity);
+        }
+        let mut weights = vec![self.leaf_weight, self.negation_weight];
+        weights.extend(self.op_weights.iter().map(|(_, weight)| *weight));
+        return match random.weighted(&weights) {
+            0 => self.leaf(random, polarity),
+            1 => {
+                let inner = self.node(random, depth - 1, polarity.map(|it| !it));
+                BoolExpr::Not(Box::new(inner))
+            }
+            i => {
+                let op = self.op_weights[i - 2].0;
+                let (left, right) = match op {
+                    BoolOp::And | BoolOp::Or => (polarity, polarity),
+                    BoolOp::Imp => (polarity.map(|it| !it), polarity),
+                    BoolOp::Xor | BoolOp::Iff => (None, None),
+                };
+                BoolExpr::Op {
+                    op,
+                    left: Box::new(self.node(random, depth - 1, left)),
+                    right: Box::new(self.node(random, depth - 1, right)),
+                }
+            }
+        };
+    }
+
+    /// **(internal)** Generate a constant or a literal of an atom which is allowed in a node
+    /// with the given `polarity`.
+    fn leaf(&self, random: &mut Random, polarity: Option<bool>) -> BoolExpr<usize> {
+        let allowed: Vec<usize> = (0..self.atom_count)
+            .filter(|atom| match self.monotonicity[*atom] {
+                None => true,
+                Some(Monotonicity::Inessential) => false,
+                Some(_) => polarity.is_some(),
+            })
+            .collect();
+        if allowed.is_empty() || random.chance(self.constant_probability) {
+            return BoolExpr::Const(random.chance(0.5));
+        }
+        let atom = allowed[random.below(allowed.len())];
+        // A positive literal keeps the polarity of the node.
+        let positive = match (self.monotonicity[atom], polarity) {
+            (Some(Monotonicity::Positive), Some(polarity)) => polarity,
+            (Some(Monotonicity::Negative), Some(polarity)) => !polarity,
+            _ => random.chance(0.5),
+        };
+        return if positive {
+            BoolExpr::Atom(atom)
+        } else {
+            BoolExpr::Not(Box::new(BoolExpr::Atom(atom)))
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::bdd::BddManager;
+    use crate::collections::bitvectors::{ArrayBitVector, BitVector};
+    use crate::logic::{BoolExpr, BoolOp, Monotonicity};
+    use crate::testing::{ExprGenerator, Random};
+
+    fn depth(expr: &BoolExpr<usize>) -> usize {
+        return expr.fold(|_, children: Vec<usize>| {
+            children.into_iter().max().map(|it| it + 1).unwrap_or(0)
+        });
+    }
+
+    #[test]
+    fn generator_respects_configuration() {
+        let generator = ExprGenerator::new(5, 6)
+            .with_op_weight(BoolOp::Xor, 0)
+            .with_op_weight(BoolOp::Iff, 0)
+            .with_negation_weight(0)
+            .with_leaf_weight(0);
+        let mut random = Random::new(3);
+        for _ in 0..20 {
+            let expr = generator.generate(&mut random);
+            assert!(depth(&expr) <= 7);
+            assert!(expr.atoms().iter().all(|atom| *atom < 5));
+            expr.fold(|node, _: Vec<()>| {
+                if let BoolExpr::Op { op, .. } = node {
+                    assert!(*op != BoolOp::Xor && *op != BoolOp::Iff);
+                }
+            });
+        }
+        let constants = ExprGenerator::new(3, 4).with_constant_probability(1.0);
+        assert!(constants.generate(&mut random).atoms().is_empty());
+    }
+
+    #[test]
+    fn generator_monotonicity() {
+        let generator = ExprGenerator::new(4, 5)
+            .with_monotonicity(0, Monotonicity::Positive)
+            .with_monotonicity(1, Monotonicity::Negative)
+            .with_monotonicity(2, Monotonicity::Inessential);
+        let mut random = Random::new(11);
+        for _ in 0..30 {
+            let expr = generator.generate(&mut random);
+            assert!(!expr.atoms().contains(&2));
+            for (atom, effect) in expr.atom_effects() {
+                let allowed = match atom {
+                    0 => vec![Monotonicity::Positive, Monotonicity::Inessential],
+                    1 => vec![Monotonicity::Negative, Monotonicity::Inessential],
+                    _ => continue,
+                };
+                assert!(allowed.contains(&effect.monotonicity), "{}", expr);
+            }
+        }
+    }
+
+    #[test]
+    fn fuzz_solvers_and_converters() {
+        // Compare the truth tables of all equivalent forms of random expressions.
+        let generator = ExprGenerator::new(6, 5).with_constant_probability(0.1);
+        let mut random = Random::new(2020);
+        let atoms: Vec<usize> = (0..6).collect();
+        let mut manager = BddManager::new(6);
+        for _ in 0..50 {
+            let expr = generator.generate(&mut random);
+            let table = expr.truth_table(&atoms);
+            let forms = vec![
+                expr.to_nnf(),
+                expr.to_cnf(),
+                expr.to_dnf(),
+                expr.simplify(),
+                expr.minimize(),
+                expr.flatten(),
+            ];
+            for form in forms {
+                assert_eq!(table, form.truth_table(&atoms), "{}", expr);
+            }
+            let bdd = manager.from_expr(&expr);
+            let count = table.ones().len() as u64;
+            assert_eq!(Some(count), manager.count(bdd).to_u64());
+            assert_eq!(Some(count), expr.count_models(&atoms).to_u64());
+            assert_eq!(count > 0, expr.is_satisfiable());
+            assert_eq!(count == 64, expr.is_tautology());
+            let state: ArrayBitVector = random.bit_vector(6);
+            assert_eq!(expr.eval_bit_vector(&state), manager.eval(bdd, &state));
+        }
+    }
+}
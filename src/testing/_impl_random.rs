@@ -0,0 +1,107 @@
+use super::Random;
+use crate::collections::bitvectors::BitVector;
+
+impl Random {
+    /// Create a new generator initialized with the given `seed`.
+    pub fn new(seed: u64) -> Random {
+        return Random { state: seed };
+    }
+
+    /// Next uniformly distributed 64-bit number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        return z ^ (z >> 31);
+    }
+
+    /// A number from the range `0..bound` (with negligible bias for reasonable bounds).
+    ///
+    /// Panics if `bound` is zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            panic!("Cannot choose a number below zero.");
+        }
+        return ((self.next_u64() as u128 * bound as u128) >> 64) as usize;
+    }
+
+    /// A random `bool` which is `true` with the given `probability`.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        // 53 random bits give a uniformly distributed float from `[0, 1)`.
+        let sample = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        return sample < probability;
+    }
+
+    /// Index of an item chosen randomly according to the given relative `weights`.
+    ///
+    /// Panics if all weights are zero.
+    pub fn weighted(&mut self, weights: &[u32]) -> usize {
+        let total: u64 = weights.iter().map(|w| *w as u64).sum();
+        if total == 0 {
+            panic!("Cannot choose from items with zero total weight.");
+        }
+        let mut sample = self.below(total as usize) as u64;
+        for (i, weight) in weights.iter().enumerate() {
+            if sample < *weight as u64 {
+                return i;
+            }
+            sample -= *weight as u64;
+        }
+        unreachable!()
+    }
+
+    /// A uniformly distributed random `BitVector` of the given length.
+    pub fn bit_vector<BV: BitVector>(&mut self, len: usize) -> BV {
+        return self.bit_vector_with_density(len, 0.5);
+    }
+
+    /// A random `BitVector` of the given length where every value is `true` with the given
+    /// probability (independently of the other values).
+    pub fn bit_vector_with_density<BV: BitVector>(&mut self, len: usize, density: f64) -> BV {
+        let values: Vec<bool> = (0..len).map(|_| self.chance(density)).collect();
+        return BV::from(values);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collections::bitvectors::{ArrayBitVector, BitVector, BitVector58};
+    use crate::testing::Random;
+
+    #[test]
+    fn random_is_deterministic() {
+        let mut a = Random::new(7);
+        let mut b = Random::new(7);
+        let mut c = Random::new(8);
+        let first: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+        assert_eq!(first, (0..10).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(first, (0..10).map(|_| c.next_u64()).collect::<Vec<_>>());
+        let x: ArrayBitVector = a.bit_vector(100);
+        let y: ArrayBitVector = b.bit_vector(100);
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn random_distributions() {
+        let mut random = Random::new(1);
+        let mut counts = [0; 4];
+        for _ in 0..10_000 {
+            counts[random.weighted(&[1, 0, 2, 1])] += 1;
+        }
+        assert_eq!(0, counts[1]);
+        assert!(counts[2] > 4_500 && counts[2] < 5_500);
+        assert!(counts[0] > 2_000 && counts[3] > 2_000);
+        assert!((0..1000).all(|_| random.below(7) < 7));
+        let sparse: ArrayBitVector = random.bit_vector_with_density(50, 0.0);
+        assert!(sparse.ones().is_empty());
+        let dense: BitVector58 = random.bit_vector_with_density(20, 1.0);
+        assert!(dense.zeros().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn random_zero_weights() {
+        Random::new(0).weighted(&[0, 0]);
+    }
+}
@@ -0,0 +1,73 @@
+//! Utilities for randomized (property-based) testing: a seeded pseudo-random number
+//! generator and generators of random `BoolExpr`s and `BitVector`s.
+//!
+//! This module is only available in the tests of this crate or with the `testing` feature,
+//! so that downstream crates can use it in their own test suites:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! biodivine-lib-std = { version = "0.1.0", features = ["testing"] }
+//! ```
+//!
+//! All generators are deterministic: the same seed and configuration always produce the
+//! same values, so a failing test case can be reproduced from its seed.
+//!
+//! ```rust
+//! use biodivine_lib_std::collections::bitvectors::{BitVector, BitVector58};
+//! use biodivine_lib_std::logic::{BoolOp, Monotonicity};
+//! use biodivine_lib_std::testing::{ExprGenerator, Random};
+//! let generator = ExprGenerator::new(4, 5)
+//!     .with_op_weight(BoolOp::Xor, 0)
+//!     .with_monotonicity(0, Monotonicity::Positive);
+//! let mut random = Random::new(42);
+//! let expr = generator.generate(&mut random);
+//! assert!(expr.atoms().iter().all(|atom| *atom < 4));
+//! let state: BitVector58 = random.bit_vector(4);
+//! assert_eq!(expr.eval_bit_vector(&state), expr.minimize().eval_bit_vector(&state));
+//! // The same seed gives the same expression.
+//! assert_eq!(expr, generator.generate(&mut Random::new(42)));
+//! ```
+//!
+//! ### Operator distribution
+//!
+//! `ExprGenerator` builds a random tree of at most the given depth. Every inner node is
+//! either a negation or one of the binary `BoolOp`s, chosen randomly according to
+//! configurable relative weights (by default, all of them have weight `1`). A node is
+//! replaced by a leaf (an atom or a constant) with probability given by the leaf weight
+//! relative to the weights of the operators, and always at the maximal depth.
+//!
+//! ### Monotonicity constraints
+//!
+//! Atoms can be required to be `Positive` or `Negative` (such atoms only appear with the
+//! given polarity, so the generated function is monotone in them or does not depend on them
+//! at all) or `Inessential` (such atoms do not appear at all). Constrained atoms are never
+//! placed below `Xor`/`Iff`, since their polarity would not be defined there.
+
+use crate::logic::{BoolOp, Monotonicity};
+
+mod _impl_expr_generator;
+mod _impl_random;
+
+/// A small, fast and seeded pseudo-random number generator (SplitMix64). It is not suitable
+/// for cryptographic purposes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Random {
+    state: u64,
+}
+
+/// A configurable generator of random `BoolExpr<usize>` over atoms `0..atom_count`.
+/// For usage examples, see module description.
+#[derive(Clone, Debug)]
+pub struct ExprGenerator {
+    atom_count: usize,
+    depth: usize,
+    /// Relative weights of the inner nodes.
+    negation_weight: u32,
+    op_weights: Vec<(BoolOp, u32)>,
+    /// Relative weights of the leaves (used to stop before reaching the maximal depth).
+    leaf_weight: u32,
+    /// Probability that a leaf is a constant instead of an atom.
+    constant_probability: f64,
+    /// `monotonicity[i]` is the constraint of atom `i` (if any).
+    monotonicity: Vec<Option<Monotonicity>>,
+}